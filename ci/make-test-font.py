@@ -0,0 +1,139 @@
+"""Writes rustmath/src/tests/fonts/kerning.ttf, a tiny text font for the tests.
+
+It has rectangles as glyphs, no MATH table and a GPOS `kern` feature with the
+pairs in KERNING. Run from the root of the repository.
+"""
+import pathlib
+import struct
+
+CHARS = 'ABTVoabvxy12+=?'
+UNITS_PER_EM = 1000
+ADVANCE = 600
+KERNING = {('A', 'V'): -80, ('T', 'o'): -90}
+
+OUTPUT = pathlib.Path('rustmath/src/tests/fonts/kerning.ttf')
+
+
+def box(ch):
+    """The bounding box of a glyph: capitals and digits are taller"""
+    if ch is None:
+        return (50, 0, 550, 700)
+    if ch in '+=':
+        return (50, 100, 550, 600)
+    if ch.isupper() or ch.isdigit() or ch in 'b?':
+        return (50, 0, 550, 700)
+    return (50, 0, 550, 500)
+
+
+def glyph(ch):
+    x0, y0, x1, y1 = box(ch)
+    points = [(x0, y0), (x0, y1), (x1, y1), (x1, y0)]
+    data = struct.pack('>hhhhh', 1, x0, y0, x1, y1)
+    data += struct.pack('>HH', len(points) - 1, 0)
+    data += bytes([0x01] * len(points))
+    previous = (0, 0)
+    xs, ys = b'', b''
+    for x, y in points:
+        xs += struct.pack('>h', x - previous[0])
+        ys += struct.pack('>h', y - previous[1])
+        previous = (x, y)
+    data += xs + ys
+    return data + b'\0' * (-len(data) % 4)
+
+
+def cmap(glyph_ids):
+    segments = sorted((ord(ch), gid) for ch, gid in glyph_ids.items())
+    segments.append((0xFFFF, 0))
+    count = len(segments)
+    search_range = 2 ** (count.bit_length() - 1) * 2
+    subtable = struct.pack('>HHHHHHH', 4, 0, 0, count * 2, search_range,
+                           (search_range // 2).bit_length() - 1, count * 2 - search_range)
+    subtable += b''.join(struct.pack('>H', code) for code, _ in segments)
+    subtable += struct.pack('>H', 0)
+    subtable += b''.join(struct.pack('>H', code) for code, _ in segments)
+    subtable += b''.join(struct.pack('>H', (gid - code) % 0x10000 if code != 0xFFFF else 1)
+                         for code, gid in segments)
+    subtable += b''.join(struct.pack('>H', 0) for _ in segments)
+    subtable = subtable[:2] + struct.pack('>H', len(subtable)) + subtable[4:]
+    return struct.pack('>HHHHI', 0, 1, 3, 1, 12) + subtable
+
+
+def gpos(glyph_ids):
+    firsts = sorted({glyph_ids[first] for first, _ in KERNING})
+    pair_sets = []
+    for first in firsts:
+        pairs = sorted((glyph_ids[second], value)
+                       for (left, second), value in KERNING.items() if glyph_ids[left] == first)
+        pair_sets.append(struct.pack('>H', len(pairs)) +
+                         b''.join(struct.pack('>Hh', second, value) for second, value in pairs))
+
+    coverage = struct.pack('>HH', 1, len(firsts)) + b''.join(struct.pack('>H', g) for g in firsts)
+    header_size = 10 + 2 * len(pair_sets)
+    offsets, offset = [], header_size + len(coverage)
+    for pair_set in pair_sets:
+        offsets.append(offset)
+        offset += len(pair_set)
+    subtable = struct.pack('>HHHHH', 1, header_size, 0x0004, 0, len(pair_sets))
+    subtable += b''.join(struct.pack('>H', o) for o in offsets) + coverage + b''.join(pair_sets)
+
+    script_list = struct.pack('>H4sH', 1, b'DFLT', 8) + struct.pack('>HH', 4, 0)
+    script_list += struct.pack('>HHHH', 0, 0xFFFF, 1, 0)
+    feature_list = struct.pack('>H4sH', 1, b'kern', 8) + struct.pack('>HHH', 0, 1, 0)
+    lookup_list = struct.pack('>HH', 1, 4) + struct.pack('>HHHH', 2, 0, 1, 8) + subtable
+
+    offset = 10
+    header = struct.pack('>HHHHH', 1, 0, offset, offset + len(script_list),
+                         offset + len(script_list) + len(feature_list))
+    return header + script_list + feature_list + lookup_list
+
+
+def checksum(data):
+    data += b'\0' * (-len(data) % 4)
+    return sum(struct.unpack(f'>{len(data) // 4}I', data)) & 0xFFFFFFFF
+
+
+def main():
+    glyph_ids = {ch: idx + 1 for idx, ch in enumerate(CHARS)}
+    glyphs = [glyph(None)] + [glyph(ch) for ch in CHARS]
+    count = len(glyphs)
+
+    offsets = [0]
+    for data in glyphs:
+        offsets.append(offsets[-1] + len(data))
+
+    tables = {
+        b'GPOS': gpos(glyph_ids),
+        b'cmap': cmap(glyph_ids),
+        b'glyf': b''.join(glyphs),
+        b'head': struct.pack('>IIIIHHQQhhhhHHhhh', 0x00010000, 0x00010000, 0, 0x5F0F3CF5, 3,
+                             UNITS_PER_EM, 0, 0, 50, 0, 550, 700, 0, 8, 2, 1, 0),
+        b'hhea': struct.pack('>IhhhHhhhhhhhhhhhH', 0x00010000, 800, -200, 0, ADVANCE, 50, 50,
+                             550, 1, 0, 0, 0, 0, 0, 0, 0, count),
+        b'hmtx': b''.join(struct.pack('>Hh', ADVANCE, box(ch)[0]) for ch in [None, *CHARS]),
+        b'loca': b''.join(struct.pack('>I', offset) for offset in offsets),
+        b'maxp': struct.pack('>IHHHHHHHHHHHHHH', 0x00010000, count, 4, 1, 0, 0, 2, 0, 0, 0, 0, 0,
+                             0, 0, 0),
+    }
+
+    entry_selector = len(tables).bit_length() - 1
+    search_range = 2 ** entry_selector * 16
+    font = struct.pack('>IHHHH', 0x00010000, len(tables), search_range, entry_selector,
+                       len(tables) * 16 - search_range)
+    offset = len(font) + 16 * len(tables)
+    directory, body = b'', b''
+    for tag, data in sorted(tables.items()):
+        directory += struct.pack('>4sIII', tag, checksum(data), offset + len(body), len(data))
+        body += data + b'\0' * (-len(data) % 4)
+    font += directory + body
+
+    # The checksum adjustment of the head table makes the whole font sum up
+    # to a magic number
+    head = font.index(struct.pack('>II', 0x00010000, 0x00010000), offset)
+    adjustment = (0xB1B0AFBA - checksum(font)) & 0xFFFFFFFF
+    font = font[:head + 8] + struct.pack('>I', adjustment) + font[head + 12:]
+
+    OUTPUT.parent.mkdir(parents=True, exist_ok=True)
+    OUTPUT.write_bytes(font)
+
+
+main()
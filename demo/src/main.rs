@@ -7,13 +7,13 @@ mod uploader;
 
 fn generate_image_url(src: &str, include_metadata: bool, png: bool) -> Option<String> {
     let (prefix, encoded) = if png {
-        let image_data = rustmath::encode_png(src, include_metadata)?;
+        let image_data = rustmath::encode_png(src, include_metadata).ok()?;
         let prefix = "data:image/png;base64,";
         let encoded = base64::engine::general_purpose::STANDARD.encode(&image_data);
 
         (prefix, encoded)
     } else {
-        let image_data = rustmath::render_svg(src, include_metadata)?;
+        let image_data = rustmath::render_svg(src, include_metadata).ok()?;
         let prefix = "data:image/svg+xml;base64,";
         let encoded = base64::engine::general_purpose::STANDARD.encode(&image_data);
 
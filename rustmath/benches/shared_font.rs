@@ -0,0 +1,74 @@
+//! Compares parsing the font for every rendering with sharing one parsed
+//! `FontSet` between renderings, output formats and threads.
+//!
+//! Run with `cargo bench --bench shared_font`
+
+use rustmath::backend::opentype::{EmbeddedFont, FontBackend, FontSet};
+use rustmath::backend::svg::SvgRenderer;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const SRC: &str = "\\sum_{k=1}^n \\frac{1}{k^2} = \\int_0^1 \\left(1 - x^2\\right) dx";
+const ITERATIONS: u32 = 200;
+const THREADS: u32 = 4;
+
+fn render(fb: &FontBackend<SvgRenderer>) {
+    rustmath::render_svg_with_font(SRC, false, fb, &Default::default()).unwrap();
+}
+
+fn measure(name: &str, f: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    f();
+    let elapsed = start.elapsed();
+    println!(
+        "{name:<32} {:>10.1?} total, {:>8.1?} per rendering",
+        elapsed,
+        elapsed / ITERATIONS
+    );
+    elapsed
+}
+
+fn main() {
+    // Warm up
+    render(&FontBackend::new_embedded(EmbeddedFont::default()));
+
+    let parse = measure("parse font for every rendering", || {
+        for _ in 0..ITERATIONS {
+            let fb = FontBackend::<SvgRenderer>::new_embedded(EmbeddedFont::default());
+            render(&fb);
+        }
+    });
+
+    let fonts = Arc::new(FontSet::new_embedded(EmbeddedFont::default()));
+    let shared = measure("shared font set, new backend", || {
+        for _ in 0..ITERATIONS {
+            let fb = FontBackend::<SvgRenderer>::new(fonts.clone());
+            render(&fb);
+        }
+    });
+
+    let fb = FontBackend::<SvgRenderer>::new(fonts.clone());
+    let cached = measure("shared backend (glyph cache)", || {
+        for _ in 0..ITERATIONS {
+            render(&fb);
+        }
+    });
+
+    measure("shared backend, threads", || {
+        std::thread::scope(|scope| {
+            for _ in 0..THREADS {
+                scope.spawn(|| {
+                    for _ in 0..ITERATIONS / THREADS {
+                        render(&fb);
+                    }
+                });
+            }
+        });
+    });
+
+    println!(
+        "\nsaved by sharing the font set: {:.1?} per rendering ({:.1?} with the glyph cache)",
+        parse.saturating_sub(shared) / ITERATIONS,
+        parse.saturating_sub(cached) / ITERATIONS,
+    );
+}
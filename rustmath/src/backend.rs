@@ -1,7 +1,21 @@
+#[cfg(any(feature = "pdf", feature = "eps", feature = "svg"))]
+mod number;
+
+#[cfg(feature = "eps")]
+pub mod eps;
+
+#[cfg(any(feature = "pdf", feature = "eps"))]
+mod outline;
+
 pub mod opentype;
 
+#[cfg(feature = "pdf")]
+pub mod pdf;
+
 #[cfg(feature = "tiny-skia")]
 pub mod raster;
 
 #[cfg(feature = "svg")]
 pub mod svg;
+
+pub mod text;
@@ -0,0 +1,292 @@
+use super::{
+    number::Number,
+    opentype,
+    outline::{CubicOutlineBuilder, PathOperators},
+};
+use crate::{common::Color, options::Rgba, RenderOptions};
+use std::fmt::Write;
+
+/// The PostScript path construction operators
+pub struct PostScriptOperators;
+
+impl PathOperators for PostScriptOperators {
+    const MOVE_TO: &'static str = "moveto";
+    const LINE_TO: &'static str = "lineto";
+    const CURVE_TO: &'static str = "curveto";
+    const CLOSE: &'static str = "closepath";
+}
+
+pub type OutlineBuilder = CubicOutlineBuilder<PostScriptOperators>;
+
+/// An Encapsulated PostScript document of the size of the formula
+#[derive(Clone)]
+pub struct Document {
+    width: f32,
+    height: f32,
+    /// The PostScript program, without the header
+    body: String,
+    current_fill: Option<Rgba>,
+}
+
+impl Document {
+    /// A background of `None` means transparent
+    pub fn new(width: f32, height: f32, background: Option<Rgba>) -> Self {
+        let mut document = Self {
+            width,
+            height,
+            body: String::new(),
+            current_fill: None,
+        };
+
+        if let Some(background) = background {
+            document.draw_rect(0.0, 0.0, width, height, background);
+        }
+
+        document
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    /// PostScript has no transparency, so the alpha channel is ignored
+    fn set_fill(&mut self, color: Rgba) {
+        if self.current_fill == Some(color) {
+            return;
+        }
+        self.current_fill = Some(color);
+
+        let component = |c: u8| Number(c as f32 / 255.0);
+        let _ = writeln!(
+            self.body,
+            "{} {} {} setrgbcolor",
+            component(color.red),
+            component(color.green),
+            component(color.blue)
+        );
+    }
+
+    pub fn draw_rect(&mut self, x0: f32, y0: f32, width: f32, height: f32, fill: Rgba) {
+        self.set_fill(fill);
+        let _ = writeln!(
+            self.body,
+            "{} {} {} {} rectfill",
+            Number(x0),
+            Number(y0),
+            Number(width),
+            Number(height)
+        );
+    }
+
+    /// Draws a path, given in PostScript operators, at the given position
+    pub fn draw_path(&mut self, translate_x: f32, translate_y: f32, path: &str, fill: Rgba) {
+        if path.is_empty() {
+            return;
+        }
+
+        self.set_fill(fill);
+        let _ = writeln!(
+            self.body,
+            "gsave {} {} translate newpath {}fill grestore",
+            Number(translate_x),
+            Number(translate_y),
+            path
+        );
+    }
+
+    /// Writes the document. The metadata is stored in DSC comments of the
+    /// header
+    pub fn write(
+        &self,
+        metadata: &[(&str, &str)],
+        out: &mut impl core::fmt::Write,
+    ) -> core::fmt::Result {
+        writeln!(out, "%!PS-Adobe-3.0 EPSF-3.0")?;
+        writeln!(
+            out,
+            "%%BoundingBox: 0 0 {} {}",
+            self.width.ceil() as i32,
+            self.height.ceil() as i32
+        )?;
+        writeln!(
+            out,
+            "%%HiResBoundingBox: 0 0 {} {}",
+            Number(self.width),
+            Number(self.height)
+        )?;
+        writeln!(out, "%%Creator: rustmath")?;
+        writeln!(out, "%%LanguageLevel: 2")?;
+        writeln!(out, "%%Pages: 1")?;
+        for (key, value) in metadata {
+            write_comment(key, value, out)?;
+        }
+        writeln!(out, "%%EndComments")?;
+        writeln!(out, "%%Page: 1 1")?;
+        writeln!(out, "save")?;
+        out.write_str(&self.body)?;
+        writeln!(out, "restore")?;
+        writeln!(out, "showpage")?;
+        writeln!(out, "%%EOF")
+    }
+}
+
+/// DSC lines must not be longer than 255 characters. Longer comments are
+/// continued with `%%+`
+const MAX_COMMENT_VALUE: usize = 200;
+
+/// Writes a comment whose value is a PostScript string. Characters outside of
+/// printable ASCII are escaped, so the file stays 7-bit clean
+fn write_comment(key: &str, value: &str, out: &mut impl core::fmt::Write) -> core::fmt::Result {
+    let mut escaped = String::from("(");
+    let mut buf = [0; 4];
+    for ch in value.chars() {
+        match ch {
+            '\\' | '(' | ')' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            ' '..='~' => escaped.push(ch),
+            _ => {
+                for byte in ch.encode_utf8(&mut buf).bytes() {
+                    write!(escaped, "\\{:03o}", byte)?;
+                }
+            }
+        }
+    }
+    escaped.push(')');
+
+    let mut chunks = escaped.as_bytes().chunks(MAX_COMMENT_VALUE);
+    // The escaped string is ASCII, so chunks are valid UTF-8
+    let first = chunks.next().unwrap_or_default();
+    writeln!(out, "%%{}: {}", key, String::from_utf8_lossy(first))?;
+    for chunk in chunks {
+        writeln!(out, "%%+ {}", String::from_utf8_lossy(chunk))?;
+    }
+    Ok(())
+}
+
+/// Returns the comments of the header whose value is a PostScript string
+pub fn parse_metadata(eps: &str) -> Option<Vec<(&str, String)>> {
+    let mut lines = eps.lines();
+    if !lines.next()?.starts_with("%!PS-Adobe") {
+        return None;
+    }
+
+    let mut comments: Vec<(&str, String)> = Vec::new();
+    for line in lines {
+        if line.starts_with("%%EndComments") || !line.starts_with("%%") {
+            break;
+        }
+
+        if let Some(continuation) = line.strip_prefix("%%+ ") {
+            if let Some((_, value)) = comments.last_mut() {
+                value.push_str(continuation);
+            }
+        } else if let Some((key, value)) = line[2..].split_once(": ") {
+            comments.push((key, value.to_owned()));
+        }
+    }
+
+    let result = comments
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, parse_string(&value)?)))
+        .collect();
+    Some(result)
+}
+
+/// Parses a PostScript string literal, as written by `write_comment`
+fn parse_string(s: &str) -> Option<String> {
+    let s = s.strip_prefix('(')?.strip_suffix(')')?;
+    let mut bytes = Vec::new();
+    let mut chars = s.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+
+        match chars.next()? {
+            digit @ '0'..='7' => {
+                let mut value = digit.to_digit(8)?;
+                for _ in 0..2 {
+                    match chars.peek().and_then(|ch| ch.to_digit(8)) {
+                        Some(digit) => {
+                            value = value * 8 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                bytes.push(value as u8);
+            }
+            'n' => bytes.push(b'\n'),
+            'r' => bytes.push(b'\r'),
+            't' => bytes.push(b'\t'),
+            ch => bytes.push(ch as u8),
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+#[derive(Clone)]
+pub struct EpsRenderer {
+    document: Document,
+    foreground: Rgba,
+    error_color: Rgba,
+}
+
+impl opentype::OpenTypeRenderer for EpsRenderer {
+    type Path = String;
+    type OutlineBuilder = OutlineBuilder;
+
+    type Image = Document;
+
+    fn new(width: f32, height: f32, options: &RenderOptions) -> crate::Result<Self> {
+        Ok(Self {
+            document: Document::new(width, height, options.background),
+            foreground: options.foreground,
+            error_color: options.error_color,
+        })
+    }
+
+    fn render_path(&mut self, x0: f32, y0: f32, path: &Self::Path, color: Color) {
+        let fill = match color {
+            Color::Normal => self.foreground,
+            Color::Error => self.error_color,
+        };
+        self.document.draw_path(x0, y0, path, fill);
+    }
+
+    fn render_box(&mut self, x0: f32, y0: f32, width: f32, height: f32) {
+        self.document
+            .draw_rect(x0, y0, width, height, self.foreground);
+    }
+
+    fn finish(self) -> Self::Image {
+        self.document
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_comment_round_trip() {
+        let value = "\\frac{(a)}{b} ä".repeat(30);
+        let mut eps = String::from("%!PS-Adobe-3.0 EPSF-3.0\n");
+        super::write_comment("rustmath_src", &value, &mut eps).unwrap();
+        eps.push_str("%%EndComments\n");
+
+        assert!(eps.lines().all(|line| line.len() <= 255));
+        assert!(eps.contains("\n%%+ "));
+
+        let metadata = super::parse_metadata(&eps).unwrap();
+        assert_eq!(metadata, vec![("rustmath_src", value)]);
+    }
+}
@@ -0,0 +1,31 @@
+/// Formats a number with at most the given number of decimals, without
+/// trailing zeros and exponents
+#[derive(Clone, Copy)]
+pub(crate) struct Rounded(pub f32, pub usize);
+
+impl core::fmt::Display for Rounded {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let s = format!("{:.*}", self.1, self.0);
+        let s = match s.contains('.') {
+            true => s.trim_end_matches('0').trim_end_matches('.'),
+            false => &s,
+        };
+        match s {
+            "-0" => f.write_str("0"),
+            s => f.write_str(s),
+        }
+    }
+}
+
+/// Formats a number for PDF and PostScript output, which do not allow
+/// exponents. Three decimals are more than enough for coordinates in pt
+#[cfg(any(feature = "pdf", feature = "eps"))]
+#[derive(Clone, Copy)]
+pub(crate) struct Number(pub f32);
+
+#[cfg(any(feature = "pdf", feature = "eps"))]
+impl core::fmt::Display for Number {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Rounded(self.0, 3).fmt(f)
+    }
+}
@@ -1,9 +1,30 @@
 // Reexport ttf_parser
 pub use ttf_parser;
 
-use crate::common::{self, construction::ConstructionPart, Color, Construction, FontStyle};
+use crate::common::{
+    self, construction::ConstructionPart, Color, Construction, Corner, Family, FontStyle,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
 use ttf_parser::{Face, GlyphId};
 
+mod cache;
+mod constants;
+mod embedding;
+mod inspect;
+
+#[cfg(feature = "embedded-font")]
+mod embedded;
+
+pub use cache::CacheStats;
+pub use constants::MathConstants;
+pub use inspect::{
+    AssemblyPart, CharVariants, CommandCoverage, GlyphAssembly, GlyphConstruction, GlyphVariant,
+};
+
+#[cfg(feature = "embedded-font")]
+pub use embedded::EmbeddedFont;
+
 pub trait OutlineBuilder<Path>: ttf_parser::OutlineBuilder + Default {
     fn finish(self, scale: f32) -> Path;
 }
@@ -13,23 +34,60 @@ pub trait OpenTypeRenderer: Clone {
     type OutlineBuilder: OutlineBuilder<Self::Path>;
     type Image;
 
-    fn new(width: f32, height: f32) -> Self;
+    fn new(width: f32, height: f32, options: &crate::RenderOptions) -> crate::Result<Self>;
     fn render_path(&mut self, x0: f32, y0: f32, path: &Self::Path, color: crate::common::Color);
     fn render_box(&mut self, x0: f32, y0: f32, width: f32, height: f32);
 
+    /// Renders the outline of the glyph by default. Renderers may override
+    /// this to refer to the glyph itself (e.g. as text)
+    fn render_glyph(&mut self, x0: f32, y0: f32, glyph: &Glyph<Self>, color: crate::common::Color) {
+        self.render_path(x0, y0, &glyph.path, color);
+    }
+
+    fn begin_group(&mut self, _atom_type: &crate::mathlist::AtomType) {}
+    fn end_group(&mut self) {}
+
     fn finish(self) -> Self::Image;
 }
 
 #[derive(Clone)]
 pub struct Glyph<R: OpenTypeRenderer> {
     pub id: GlyphId,
+    /// Index of the face within the font backend
+    pub face: u16,
+    /// Font size in pt, including the size factor of the face
+    pub size: f32,
     pub height: f32,
     pub depth: f32,
     pub advance: f32,
     pub italic_correction: f32,
+    pub ink_left: f32,
+    pub ink_right: f32,
+    /// Kerning of the corners, in the order of `Corner`
+    pub math_kern: Option<Arc<[MathKern; 4]>>,
     pub path: R::Path,
 }
 
+/// Staircase kerning of a glyph corner (OpenType MathKern), in pt
+#[derive(Clone, Default, Debug)]
+pub struct MathKern {
+    /// Increasing heights at which the kerning changes
+    pub heights: Vec<f32>,
+    /// One more kern than heights: `kerns[i]` applies below `heights[i]`
+    pub kerns: Vec<f32>,
+}
+
+impl MathKern {
+    pub fn kern_at(&self, height: f32) -> f32 {
+        let idx = self
+            .heights
+            .iter()
+            .position(|&h| height < h)
+            .unwrap_or(self.heights.len());
+        self.kerns.get(idx).copied().unwrap_or(0.0)
+    }
+}
+
 impl<R: OpenTypeRenderer> common::Glyph for Glyph<R> {
     fn height(&self) -> f32 {
         self.height
@@ -47,6 +105,23 @@ impl<R: OpenTypeRenderer> common::Glyph for Glyph<R> {
         self.italic_correction
     }
 
+    fn ink_extent(&self) -> (f32, f32) {
+        (self.ink_left, self.ink_right)
+    }
+
+    fn math_kern(&self, corner: Corner, height: f32) -> f32 {
+        let idx = match corner {
+            Corner::TopRight => 0,
+            Corner::TopLeft => 1,
+            Corner::BottomRight => 2,
+            Corner::BottomLeft => 3,
+        };
+
+        self.math_kern
+            .as_ref()
+            .map_or(0.0, |kerns| kerns[idx].kern_at(height))
+    }
+
     fn set_advance(&mut self, advance: f32) {
         self.advance = advance;
     }
@@ -64,77 +139,364 @@ impl<R: OpenTypeRenderer> Glyph<R> {
         Some(value as f32 * scale)
     }
 
-    fn new_from_id(f: &Face, id: ttf_parser::GlyphId, size: f32) -> Option<Self> {
-        let scale = size / 1000.0;
+    fn get_math_kern(f: &Face, id: ttf_parser::GlyphId, scale: f32) -> Option<[MathKern; 4]> {
+        let info = f.tables().math?.glyph_info?.kern_infos?.get(id)?;
+
+        let convert = |kern: Option<ttf_parser::math::Kern>| {
+            let kern = match kern {
+                Some(kern) => kern,
+                None => return MathKern::default(),
+            };
+
+            MathKern {
+                heights: (0..kern.count())
+                    .filter_map(|idx| kern.height(idx))
+                    .map(|value| value.value as f32 * scale)
+                    .collect(),
+                kerns: (0..=kern.count())
+                    .filter_map(|idx| kern.kern(idx))
+                    .map(|value| value.value as f32 * scale)
+                    .collect(),
+            }
+        };
+
+        Some([
+            convert(info.top_right),
+            convert(info.top_left),
+            convert(info.bottom_right),
+            convert(info.bottom_left),
+        ])
+    }
+
+    fn new_from_id(f: &FontFace, id: ttf_parser::GlyphId, size: f32) -> Option<Self> {
+        let size = size * f.size_factor;
+        let face = f.index;
+        let f = &f.face;
+        let scale = size / f.units_per_em() as f32;
 
         let advance = f.glyph_hor_advance(id)? as f32 * scale;
 
+        // Glyphs without outline (e.g. spaces) are valid, they are just empty
         let mut outline_builder = R::OutlineBuilder::default();
-        let bounds = f.outline_glyph(id, &mut outline_builder)?;
+        let bounds = f
+            .outline_glyph(id, &mut outline_builder)
+            .unwrap_or(ttf_parser::Rect {
+                x_min: 0,
+                y_min: 0,
+                x_max: 0,
+                y_max: 0,
+            });
         let path = outline_builder.finish(scale);
 
         let italic_correction = Self::get_italic_correction(f, id, scale).unwrap_or(0.0);
+        let math_kern = Self::get_math_kern(f, id, scale).map(Arc::new);
 
         Some(Glyph {
             id,
+            face,
+            size,
             height: bounds.y_max.max(0) as f32 * scale,
             depth: (-bounds.y_min).max(0) as f32 * scale,
             advance,
             italic_correction,
+            ink_left: bounds.x_min as f32 * scale,
+            ink_right: bounds.x_max as f32 * scale,
+            math_kern,
             path,
         })
     }
+}
+
+/// A face within the fallback chain of a font
+#[derive(Clone)]
+struct FontFace<'a> {
+    /// Index of the face within the font backend
+    index: u16,
+    face: Face<'a>,
+    /// Glyphs of fallback faces are scaled to match the x-height of the primary face
+    size_factor: f32,
+    /// Dedicated faces of a family contain the plain characters. All other
+    /// faces are expected to provide the math alphanumerics instead
+    family_face: bool,
+    /// The character of each glyph in the cmap, built on first use
+    chars: OnceLock<HashMap<GlyphId, char>>,
+}
+
+impl<'a> FontFace<'a> {
+    /// x-height in em
+    fn x_height(&self) -> Option<f32> {
+        let x_height = self
+            .face
+            .x_height()
+            .filter(|&x_height| x_height > 0)
+            .or_else(|| {
+                let id = self.face.glyph_index('x')?;
+                Some(self.face.glyph_bounding_box(id)?.y_max)
+            })?;
+        Some(x_height as f32 / self.face.units_per_em() as f32)
+    }
 
-    fn new(f: &Face, ch: char, size: f32) -> Option<Self> {
-        let id = f.glyph_index(ch)?;
-        Self::new_from_id(f, id, size)
+    /// Conversion factor from font units to pt
+    fn scale(&self, size: f32) -> f32 {
+        size * self.size_factor / self.face.units_per_em() as f32
+    }
+
+    fn variants(&self) -> Option<ttf_parser::math::Variants<'a>> {
+        self.face.tables().math?.variants
+    }
+
+    /// Applies the `ssty` feature, which provides glyphs optically sized for
+    /// scripts (level 1) and second-level scripts (level 2)
+    fn script_variant(&self, id: GlyphId, style: FontStyle) -> GlyphId {
+        let level = match style {
+            FontStyle::Display | FontStyle::Text => return id,
+            FontStyle::Script => 1,
+            FontStyle::SuperScript => 2,
+        };
+
+        self.substitute(b"ssty", id, level).unwrap_or(id)
+    }
+
+    /// Looks up a single or alternate substitution of a GSUB feature. For
+    /// alternate substitutions, `level` selects the alternate (starting at 1)
+    fn substitute(&self, feature: &[u8; 4], id: GlyphId, level: u16) -> Option<GlyphId> {
+        use ttf_parser::gsub::{SingleSubstitution, SubstitutionSubtable};
+
+        for lookup in feature_lookups(self.face.tables().gsub?, feature) {
+            for subtable in lookup.subtables.into_iter::<SubstitutionSubtable>() {
+                let coverage_index = match subtable.coverage().get(id) {
+                    Some(coverage_index) => coverage_index,
+                    None => continue,
+                };
+
+                let substitute = match subtable {
+                    SubstitutionSubtable::Single(SingleSubstitution::Format1 { delta, .. }) => {
+                        Some(GlyphId((id.0 as i32 + delta as i32) as u16))
+                    }
+                    SubstitutionSubtable::Single(SingleSubstitution::Format2 {
+                        substitutes,
+                        ..
+                    }) => substitutes.get(coverage_index),
+                    SubstitutionSubtable::Alternate(alternate) => {
+                        let alternates = alternate.alternate_sets.get(coverage_index)?.alternates;
+                        let index = level.min(alternates.len()).checked_sub(1)?;
+                        alternates.get(index)
+                    }
+                    _ => None,
+                };
+
+                if substitute.is_some() {
+                    return substitute;
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Horizontal adjustment between two glyphs from the GPOS `kern` feature,
+    /// in font units
+    fn pair_kerning(&self, left: GlyphId, right: GlyphId) -> Option<i16> {
+        use ttf_parser::gpos::{PairAdjustment, PositioningSubtable};
+
+        for lookup in feature_lookups(self.face.tables().gpos?, b"kern") {
+            for subtable in lookup.subtables.into_iter::<PositioningSubtable>() {
+                let values = match subtable {
+                    PositioningSubtable::Pair(PairAdjustment::Format1 { coverage, sets }) => {
+                        coverage.get(left).and_then(|idx| sets.get(idx)?.get(right))
+                    }
+                    PositioningSubtable::Pair(PairAdjustment::Format2 {
+                        coverage,
+                        classes,
+                        matrix,
+                    }) if coverage.contains(left) => {
+                        matrix.get((classes.0.get(left), classes.1.get(right)))
+                    }
+                    _ => None,
+                };
+
+                if let Some((first, _)) = values {
+                    return Some(first.x_advance);
+                }
+            }
+        }
+
+        None
     }
 }
 
-struct Font<'a, R: OpenTypeRenderer> {
-    face: ttf_parser::Face<'a>,
-    _phantom: std::marker::PhantomData<R>,
+/// The lookups of a GSUB or GPOS feature
+fn feature_lookups<'a>(
+    table: ttf_parser::opentype_layout::LayoutTable<'a>,
+    feature: &[u8; 4],
+) -> impl Iterator<Item = ttf_parser::opentype_layout::Lookup<'a>> {
+    let lookup_indices = table
+        .features
+        .find(ttf_parser::Tag::from_bytes(feature))
+        .map(|feature| feature.lookup_indices);
+
+    lookup_indices
+        .into_iter()
+        .flatten()
+        .filter_map(move |index| table.lookups.get(index))
 }
 
-impl<'a, R: OpenTypeRenderer> Font<'a, R> {
+/// The face chain of one family. Does not depend on the renderer
+#[derive(Clone)]
+struct FamilyFont<'a> {
+    family: Family,
+    /// The dedicated face of the family (if any), followed by the primary
+    /// math face and the fallback faces
+    faces: Vec<FontFace<'a>>,
+    constants: constants::MathConstants,
+    /// Units per em of the primary math face
+    units_per_em: f32,
+}
+
+impl<'a> FamilyFont<'a> {
+    fn new(face: ttf_parser::Face<'a>) -> crate::Result<Self> {
+        let constants = match face.tables().math.and_then(|math| math.constants) {
+            Some(constants) => constants::MathConstants::from_table(&constants),
+            None => constants::MathConstants::synthesize(&face),
+        };
+
+        if face.glyph_index('?').is_none() {
+            return Err(crate::Error::GlyphMissing('?'));
+        }
+
+        let units_per_em = face.units_per_em() as f32;
+        let face = FontFace {
+            index: 0,
+            face,
+            size_factor: 1.0,
+            family_face: false,
+            chars: OnceLock::new(),
+        };
+
+        Ok(Self {
+            family: Family::Italic,
+            faces: vec![face],
+            constants,
+            units_per_em,
+        })
+    }
+
+    /// Creates a face whose glyphs match the x-height of the primary math face
+    fn make_face(&self, index: u16, face: ttf_parser::Face<'a>, family_face: bool) -> FontFace<'a> {
+        let mut face = FontFace {
+            index,
+            face,
+            size_factor: 1.0,
+            family_face,
+            chars: OnceLock::new(),
+        };
+
+        let primary = self.faces.iter().find(|face| !face.family_face);
+        if let (Some(primary), Some(face_x_height)) =
+            (primary.and_then(|p| p.x_height()), face.x_height())
+        {
+            face.size_factor = primary / face_x_height;
+        }
+
+        face
+    }
+
+    fn with_family(&self, family: Family) -> Self {
+        let mut font = self.clone();
+        font.family = family;
+        font
+    }
+
+    /// Replaces the dedicated face of the family. Characters missing in that
+    /// face are taken from the math font
+    fn set_family_face(&mut self, face: FontFace<'a>) {
+        self.faces.retain(|face| !face.family_face);
+        self.faces.insert(0, face);
+    }
+
+    /// Returns the first face in the chain which contains the given char.
+    /// Faces other than the family's own are asked for the Mathematical
+    /// Alphanumeric Symbol first. Text fonts often lack those, so the plain
+    /// char is tried afterwards
+    fn find_glyph(&self, ch: char) -> Option<(&FontFace<'a>, GlyphId)> {
+        let math_ch = self.family.math_alphanumeric(ch);
+        let find = |math_ch| {
+            self.faces.iter().find_map(|face| {
+                let ch = if face.family_face { ch } else { math_ch };
+                Some((face, face.face.glyph_index(ch)?))
+            })
+        };
+        match find(math_ch) {
+            None if math_ch != ch => find(ch),
+            found => found,
+        }
+    }
+
+    /// Like `find_glyph`, but only considers faces with size variants
+    fn find_variants(
+        &self,
+        ch: char,
+    ) -> Option<(&FontFace<'a>, ttf_parser::math::Variants<'a>, GlyphId)> {
+        let math_ch = self.family.math_alphanumeric(ch);
+        let find = |ch| {
+            self.faces.iter().find_map(|face| {
+                let variants = face.variants()?;
+                Some((face, variants, face.face.glyph_index(ch)?))
+            })
+        };
+        match find(math_ch) {
+            None if math_ch != ch => find(ch),
+            found => found,
+        }
+    }
+
+    /// Conversion factor from font units of the MATH constants to pt
+    fn scale(&self, size: f32) -> f32 {
+        size / self.units_per_em
+    }
+
     fn size_for_style(&self, size: f32, style: FontStyle) -> f32 {
         match style {
             FontStyle::Display | FontStyle::Text => size,
-            FontStyle::Script => {
-                size * (self
-                    .face
-                    .tables()
-                    .math
-                    .unwrap()
-                    .constants
-                    .unwrap()
-                    .script_percent_scale_down() as f32
-                    / 100.0)
-            }
+            FontStyle::Script => size * (self.constants.script_percent_scale_down / 100.0),
             FontStyle::SuperScript => {
-                size * (self
-                    .face
-                    .tables()
-                    .math
-                    .unwrap()
-                    .constants
-                    .unwrap()
-                    .script_script_percent_scale_down() as f32
-                    / 100.0)
+                size * (self.constants.script_script_percent_scale_down / 100.0)
             }
         }
     }
+}
+
+/// A family of a `FontSet`, producing glyphs for the renderer `R`
+struct Font<'a, R: OpenTypeRenderer> {
+    fonts: Arc<FontSet<'a>>,
+    /// Index into `Family::ALL`
+    family: usize,
+    /// Shared by the fonts of all families
+    cache: Arc<cache::GlyphCache<Glyph<R>>>,
+}
+
+impl<'a, R: OpenTypeRenderer> Font<'a, R> {
+    fn font(&self) -> &FamilyFont<'a> {
+        &self.fonts.families[self.family]
+    }
+
+    fn glyph(&self, face: &FontFace<'a>, id: GlyphId, size: f32) -> Option<Glyph<R>> {
+        self.cache.get_or_insert_with(face.index, id.0, size, || {
+            Glyph::new_from_id(face, id, size)
+        })
+    }
 
     fn get_glyph_minsize(
         &self,
+        face: &FontFace<'a>,
         construction: ttf_parser::math::GlyphConstruction<'a>,
         size: f32,
         min_size: f32,
     ) -> Option<Glyph<R>> {
-        let min_size = (min_size * 1000.0 / size - 1e-3).round() as u16;
+        let min_size = (min_size / face.scale(size) - 1e-3).round() as u16;
         for variant in construction.variants {
             if variant.advance_measurement >= min_size {
-                return Glyph::new_from_id(&self.face, variant.variant_glyph, size);
+                return self.glyph(face, variant.variant_glyph, size);
             }
         }
 
@@ -143,29 +505,34 @@ impl<'a, R: OpenTypeRenderer> Font<'a, R> {
 
     fn get_glyph_construction(
         &self,
+        face: &FontFace<'a>,
         min_overlap: f32,
         assembly: ttf_parser::math::GlyphAssembly<'a>,
         size: f32,
-    ) -> Construction<Glyph<R>> {
-        let scale = size / 1000.0;
+    ) -> Option<Construction<Glyph<R>>> {
+        let scale = face.scale(size);
         let parts = assembly
             .parts
             .into_iter()
-            .map(|part| ConstructionPart {
-                glyph: Glyph::new_from_id(&self.face, part.glyph_id, size).unwrap(),
-                size: scale * part.full_advance as f32,
-                max_start_overlap: scale * part.start_connector_length as f32,
-                max_end_overlap: scale * part.end_connector_length as f32,
-                is_extender: part.part_flags.extender(),
+            .map(|part| {
+                Some(ConstructionPart {
+                    glyph: self.glyph(face, part.glyph_id, size)?,
+                    size: scale * part.full_advance as f32,
+                    max_start_overlap: scale * part.start_connector_length as f32,
+                    max_end_overlap: scale * part.end_connector_length as f32,
+                    is_extender: part.part_flags.extender(),
+                })
             })
-            .collect();
-        Construction { min_overlap, parts }
+            .collect::<Option<_>>()?;
+        Some(Construction { min_overlap, parts })
     }
 }
 
 impl<'a, R: OpenTypeRenderer> common::Font<Glyph<R>> for Font<'a, R> {
     fn get_glyph(&self, ch: char, size: f32, style: FontStyle) -> Option<Glyph<R>> {
-        Glyph::new(&self.face, ch, self.size_for_style(size, style))
+        let (face, glyph_id) = self.font().find_glyph(ch)?;
+        let glyph_id = face.script_variant(glyph_id, style);
+        self.glyph(face, glyph_id, self.font().size_for_style(size, style))
     }
 
     fn get_larger_glyph(
@@ -175,30 +542,28 @@ impl<'a, R: OpenTypeRenderer> common::Font<Glyph<R>> for Font<'a, R> {
         style: FontStyle,
         include_italic_correction: bool,
     ) -> Option<Glyph<R>> {
-        // TODO: Do not just get the second size, but the smallest
-        // glyph which is larger than `display_operator_min_height`
-
-        let glyph_id = self.face.glyph_index(ch)?;
-        let construction = self
-            .face
-            .tables()
-            .math?
-            .variants?
-            .vertical_constructions
-            .get(glyph_id)?;
-
-        let glyph_id = construction.variants.get(1)?.variant_glyph;
-
-        let mut glyph = Glyph::new_from_id(&self.face, glyph_id, self.size_for_style(size, style))?;
-
-        // TODO: The following is an ugly hack and most likely not correct
-        // For example, the small Integral symbol has the same property. Maybe we have
-        // to use the unicode math classes again
+        let (face, variants, glyph_id) = self.font().find_variants(ch)?;
+        let construction = variants.vertical_constructions.get(glyph_id)?;
 
-        // TODO: Most likely, the problem goes away once we implement subscript/superscript
-        // kerning.
+        // Use the smallest variant which is tall enough. If there is none, use
+        // the largest one
+        let font = self.font();
+        let size = font.size_for_style(size, style);
+        let min_height = font.constants.display_operator_min_height * font.scale(size);
+        let mut glyph = match self.get_glyph_minsize(face, construction, size, min_height) {
+            Some(glyph) => glyph,
+            None => {
+                let glyph_id = construction.variants.last()?.variant_glyph;
+                self.glyph(face, glyph_id, size)?
+            }
+        };
 
-        // For large glyphs, the italic correction is already included in the advance width
+        // The script kerning of the corners does not replace this: the bundled
+        // fonts have no MathKernInfo for operator variants. Instead, the
+        // italic correction positions the scripts as in the OpenType MATH
+        // specification. The advance already covers the slant, so the
+        // superscript follows it directly and the subscript is shifted left
+        // by the italic correction
         if include_italic_correction {
             glyph.italic_correction = 0.0;
         } else {
@@ -215,16 +580,10 @@ impl<'a, R: OpenTypeRenderer> common::Font<Glyph<R>> for Font<'a, R> {
         style: FontStyle,
         min_width: f32,
     ) -> Option<Glyph<R>> {
-        let glyph_id = self.face.glyph_index(ch)?;
-        let construction = self
-            .face
-            .tables()
-            .math?
-            .variants?
-            .horizontal_constructions
-            .get(glyph_id)?;
-        let size = self.size_for_style(size, style);
-        self.get_glyph_minsize(construction, size, min_width)
+        let (face, variants, glyph_id) = self.font().find_variants(ch)?;
+        let construction = variants.horizontal_constructions.get(glyph_id)?;
+        let size = self.font().size_for_style(size, style);
+        self.get_glyph_minsize(face, construction, size, min_width)
     }
 
     fn get_glyph_minheight(
@@ -234,16 +593,10 @@ impl<'a, R: OpenTypeRenderer> common::Font<Glyph<R>> for Font<'a, R> {
         style: FontStyle,
         min_height: f32,
     ) -> Option<Glyph<R>> {
-        let glyph_id = self.face.glyph_index(ch)?;
-        let construction = self
-            .face
-            .tables()
-            .math?
-            .variants?
-            .vertical_constructions
-            .get(glyph_id)?;
-        let size = self.size_for_style(size, style);
-        self.get_glyph_minsize(construction, size, min_height)
+        let (face, variants, glyph_id) = self.font().find_variants(ch)?;
+        let construction = variants.vertical_constructions.get(glyph_id)?;
+        let size = self.font().size_for_style(size, style);
+        self.get_glyph_minsize(face, construction, size, min_height)
     }
 
     fn get_glyph_hor_construction(
@@ -252,13 +605,17 @@ impl<'a, R: OpenTypeRenderer> common::Font<Glyph<R>> for Font<'a, R> {
         size: f32,
         style: FontStyle,
     ) -> Option<common::Construction<Glyph<R>>> {
-        let glyph_id = self.face.glyph_index(ch)?;
-        let variants = self.face.tables().math?.variants?;
+        let (face, variants, glyph_id) = self.font().find_variants(ch)?;
         let min_overlap = variants.min_connector_overlap;
         let construction = variants.horizontal_constructions.get(glyph_id)?;
-        let size = self.size_for_style(size, style);
-        let scale = size / 1000.0;
-        Some(self.get_glyph_construction(min_overlap as f32 * scale, construction.assembly?, size))
+        let size = self.font().size_for_style(size, style);
+        let scale = face.scale(size);
+        self.get_glyph_construction(
+            face,
+            min_overlap as f32 * scale,
+            construction.assembly?,
+            size,
+        )
     }
 
     fn get_glyph_vert_construction(
@@ -267,13 +624,17 @@ impl<'a, R: OpenTypeRenderer> common::Font<Glyph<R>> for Font<'a, R> {
         size: f32,
         style: FontStyle,
     ) -> Option<common::Construction<Glyph<R>>> {
-        let glyph_id = self.face.glyph_index(ch)?;
-        let variants = self.face.tables().math?.variants?;
+        let (face, variants, glyph_id) = self.font().find_variants(ch)?;
         let min_overlap = variants.min_connector_overlap;
         let construction = variants.vertical_constructions.get(glyph_id)?;
-        let size = self.size_for_style(size, style);
-        let scale = size / 1000.0;
-        Some(self.get_glyph_construction(min_overlap as f32 * scale, construction.assembly?, size))
+        let size = self.font().size_for_style(size, style);
+        let scale = face.scale(size);
+        self.get_glyph_construction(
+            face,
+            min_overlap as f32 * scale,
+            construction.assembly?,
+            size,
+        )
     }
 
     fn calculate_script_params(
@@ -282,33 +643,32 @@ impl<'a, R: OpenTypeRenderer> common::Font<Glyph<R>> for Font<'a, R> {
         style: FontStyle,
         cramped: bool,
     ) -> common::font_params::ScriptParams {
-        let constants = self.face.tables().math.unwrap().constants.unwrap();
+        let font = self.font();
+        let constants = &font.constants;
 
-        let glyph_size = self.size_for_style(size, style);
-        let scale = |v: &ttf_parser::math::MathValue| v.value as f32 * glyph_size / 1000.0;
+        let glyph_size = font.size_for_style(size, style);
+        let scale = |v: f32| v * font.scale(glyph_size);
 
         let subscript = common::font_params::SubScriptParams {
-            shift_down: scale(&constants.subscript_shift_down()),
-            top_max: scale(&constants.subscript_top_max()),
-            baseline_drop_min: scale(&constants.subscript_baseline_drop_min()),
+            shift_down: scale(constants.subscript_shift_down),
+            top_max: scale(constants.subscript_top_max),
+            baseline_drop_min: scale(constants.subscript_baseline_drop_min),
         };
 
         let superscript = common::font_params::SuperScriptParams {
             shift_up: match cramped {
-                true => scale(&constants.superscript_shift_up_cramped()),
-                false => scale(&constants.superscript_shift_up()),
+                true => scale(constants.superscript_shift_up_cramped),
+                false => scale(constants.superscript_shift_up),
             },
-            bottom_min: scale(&constants.superscript_bottom_min()),
-            baseline_drop_max: scale(&constants.superscript_baseline_drop_max()),
+            bottom_min: scale(constants.superscript_bottom_min),
+            baseline_drop_max: scale(constants.superscript_baseline_drop_max),
         };
 
         common::font_params::ScriptParams {
             subscript,
             superscript,
-            sub_super_gap_min: scale(&constants.sub_superscript_gap_min()),
-            super_bottom_max_with_subscript: scale(
-                &constants.superscript_bottom_max_with_subscript(),
-            ),
+            sub_super_gap_min: scale(constants.sub_superscript_gap_min),
+            super_bottom_max_with_subscript: scale(constants.superscript_bottom_max_with_subscript),
         }
     }
 
@@ -318,13 +678,57 @@ impl<'a, R: OpenTypeRenderer> common::Font<Glyph<R>> for Font<'a, R> {
         style: FontStyle,
         _cramped: bool,
     ) -> common::font_params::GeneralParams {
-        let constants = self.face.tables().math.unwrap().constants.unwrap();
+        let font = self.font();
+        let constants = &font.constants;
 
-        let glyph_size = self.size_for_style(size, style);
-        let scale = |v: &ttf_parser::math::MathValue| v.value as f32 * glyph_size / 1000.0;
+        let glyph_size = font.size_for_style(size, style);
+        let scale = |v: f32| v * font.scale(glyph_size);
 
         common::font_params::GeneralParams {
-            axis_height: scale(&constants.axis_height()),
+            axis_height: scale(constants.axis_height),
+        }
+    }
+
+    fn calculate_limit_params(
+        &self,
+        size: f32,
+        style: FontStyle,
+        _cramped: bool,
+    ) -> common::font_params::LimitParams {
+        let font = self.font();
+        let constants = &font.constants;
+
+        let glyph_size = font.size_for_style(size, style);
+        let scale = |v: f32| v * font.scale(glyph_size);
+
+        common::font_params::LimitParams {
+            upper_gap_min: scale(constants.upper_limit_gap_min),
+            upper_baseline_rise_min: scale(constants.upper_limit_baseline_rise_min),
+            lower_gap_min: scale(constants.lower_limit_gap_min),
+            lower_baseline_drop_min: scale(constants.lower_limit_baseline_drop_min),
+        }
+    }
+
+    fn calculate_radical_params(
+        &self,
+        size: f32,
+        style: FontStyle,
+        _cramped: bool,
+    ) -> common::font_params::RadicalParams {
+        let font = self.font();
+        let constants = &font.constants;
+
+        let glyph_size = font.size_for_style(size, style);
+        let scale = |v: f32| v * font.scale(glyph_size);
+
+        let vertical_gap = match style {
+            FontStyle::Display => constants.radical_display_style_vertical_gap,
+            _ => constants.radical_vertical_gap,
+        };
+        common::font_params::RadicalParams {
+            vertical_gap: scale(vertical_gap),
+            rule_thickness: scale(constants.radical_rule_thickness),
+            extra_ascender: scale(constants.radical_extra_ascender),
         }
     }
 
@@ -334,32 +738,33 @@ impl<'a, R: OpenTypeRenderer> common::Font<Glyph<R>> for Font<'a, R> {
         style: FontStyle,
         _cramped: bool,
     ) -> common::font_params::FractionParams {
-        let constants = self.face.tables().math.unwrap().constants.unwrap();
+        let font = self.font();
+        let constants = &font.constants;
 
-        let glyph_size = self.size_for_style(size, style);
-        let scale = |v: &ttf_parser::math::MathValue| v.value as f32 * glyph_size / 1000.0;
+        let glyph_size = font.size_for_style(size, style);
+        let scale = |v: f32| v * font.scale(glyph_size);
 
         let (numerator, denominator) = match style {
             FontStyle::Display => {
                 let numerator = common::font_params::FractionPartParams {
-                    shift: scale(&constants.fraction_numerator_display_style_shift_up()),
-                    gap_min: scale(&constants.fraction_num_display_style_gap_min()),
+                    shift: scale(constants.fraction_numerator_display_style_shift_up),
+                    gap_min: scale(constants.fraction_num_display_style_gap_min),
                 };
                 let denominator = common::font_params::FractionPartParams {
-                    shift: scale(&constants.fraction_denominator_display_style_shift_down()),
-                    gap_min: scale(&constants.fraction_denom_display_style_gap_min()),
+                    shift: scale(constants.fraction_denominator_display_style_shift_down),
+                    gap_min: scale(constants.fraction_denom_display_style_gap_min),
                 };
 
                 (numerator, denominator)
             }
             _ => {
                 let numerator = common::font_params::FractionPartParams {
-                    shift: scale(&constants.fraction_numerator_shift_up()),
-                    gap_min: scale(&constants.fraction_numerator_gap_min()),
+                    shift: scale(constants.fraction_numerator_shift_up),
+                    gap_min: scale(constants.fraction_numerator_gap_min),
                 };
                 let denominator = common::font_params::FractionPartParams {
-                    shift: scale(&constants.fraction_denominator_shift_down()),
-                    gap_min: scale(&constants.fraction_denominator_gap_min()),
+                    shift: scale(constants.fraction_denominator_shift_down),
+                    gap_min: scale(constants.fraction_denominator_gap_min),
                 };
 
                 (numerator, denominator)
@@ -369,43 +774,390 @@ impl<'a, R: OpenTypeRenderer> common::Font<Glyph<R>> for Font<'a, R> {
         common::font_params::FractionParams {
             numerator,
             denominator,
-            rule_thickness: scale(&constants.fraction_rule_thickness()),
+            rule_thickness: scale(constants.fraction_rule_thickness),
         }
     }
 
+    fn get_kerning(&self, left: &Glyph<R>, right: &Glyph<R>, size: f32, style: FontStyle) -> f32 {
+        if left.face != right.face {
+            return 0.0;
+        }
+
+        let font = self.font();
+        let face = match font.faces.iter().find(|face| face.index == left.face) {
+            Some(face) => face,
+            None => return 0.0,
+        };
+
+        let size = font.size_for_style(size, style);
+        face.pair_kerning(left.id, right.id)
+            .map_or(0.0, |kern| kern as f32 * face.scale(size))
+    }
+
     fn get_fallback_glyph(&self, size: f32, style: FontStyle) -> Glyph<R> {
         // TODO: Better character?
-        Self::get_glyph(self, '?', size, style).unwrap()
+        // The existence of '?' has been checked when loading the font. If the
+        // glyph is broken anyway, we use an empty glyph instead of panicking
+        Self::get_glyph(self, '?', size, style).unwrap_or_else(|| Glyph {
+            id: GlyphId(0),
+            face: 0,
+            size,
+            height: 0.0,
+            depth: 0.0,
+            advance: 0.0,
+            italic_correction: 0.0,
+            ink_left: 0.0,
+            ink_right: 0.0,
+            math_kern: None,
+            path: R::OutlineBuilder::default().finish(0.0),
+        })
     }
 }
 
+/// Selects a face inside a font collection (`.ttc`/`.otc`). For plain font
+/// files, the only valid face is `Index(0)`
+#[derive(Clone, Copy, Debug)]
+pub enum FaceSelector<'n> {
+    Index(u32),
+    PostScriptName(&'n str),
+}
+
+impl Default for FaceSelector<'_> {
+    fn default() -> Self {
+        Self::Index(0)
+    }
+}
+
+impl FaceSelector<'_> {
+    fn parse<'a>(&self, data: &'a [u8]) -> crate::Result<Face<'a>> {
+        match *self {
+            FaceSelector::Index(index) => Ok(Face::parse(data, index)?),
+            FaceSelector::PostScriptName(name) => {
+                let num_faces = ttf_parser::fonts_in_collection(data).unwrap_or(1);
+                for index in 0..num_faces {
+                    let face = Face::parse(data, index)?;
+                    if postscript_name(&face).as_deref() == Some(name) {
+                        return Ok(face);
+                    }
+                }
+
+                Err(crate::Error::FaceNotFound(name.to_owned()))
+            }
+        }
+    }
+}
+
+fn postscript_name(face: &Face) -> Option<String> {
+    face.names()
+        .into_iter()
+        .filter(|name| name.name_id == ttf_parser::name_id::POST_SCRIPT_NAME)
+        .find_map(|name| name.to_string())
+}
+
+/// The parsed faces of a math font, including the fallback and family faces.
+/// A font set does not depend on the renderer, so it can be parsed once and
+/// shared (e.g. in an `Arc`) by the font backends of several renderers and
+/// threads
+#[derive(Clone)]
+pub struct FontSet<'a> {
+    /// One face chain per family, in the order of `Family::ALL`
+    families: Vec<FamilyFont<'a>>,
+    num_faces: u16,
+    /// Whether the MATH constants have been synthesized
+    degraded: bool,
+    // Keeps the data of owned fonts alive. Must be declared after `families`,
+    // which borrow from it
+    owned_data: Vec<Arc<[u8]>>,
+}
+
+/// A font set which owns its font data
+pub type OwnedFontSet = FontSet<'static>;
+
+impl<'a> FontSet<'a> {
+    pub fn new_from_font_data(data: &'a [u8]) -> crate::Result<Self> {
+        Self::new_from_collection(data, FaceSelector::default())
+    }
+
+    pub fn new_from_collection(data: &'a [u8], face: FaceSelector) -> crate::Result<Self> {
+        Self::new_from_face(face.parse(data)?, Vec::new())
+    }
+
+    fn new_from_face(face: Face<'a>, owned_data: Vec<Arc<[u8]>>) -> crate::Result<Self> {
+        let degraded = face.tables().math.and_then(|math| math.constants).is_none();
+        let font = FamilyFont::new(face)?;
+        let families = Family::ALL
+            .iter()
+            .map(|&family| font.with_family(family))
+            .collect();
+
+        Ok(Self {
+            families,
+            num_faces: 1,
+            degraded,
+            owned_data,
+        })
+    }
+
+    /// Returns true if the primary font has no MATH table. Such fonts are
+    /// rendered using constants derived from the general font metrics and
+    /// without size variants or glyph constructions, so the result is only an
+    /// approximation
+    pub fn is_degraded(&self) -> bool {
+        self.degraded
+    }
+
+    fn next_face_index(&mut self) -> u16 {
+        self.num_faces += 1;
+        self.num_faces - 1
+    }
+
+    /// Adds a face to the fallback chain. Characters missing in the primary
+    /// math font are looked up in the fallback faces in the order they have
+    /// been added. The MATH constants are always taken from the primary font
+    pub fn add_fallback_font(&mut self, data: &'a [u8], face: FaceSelector) -> crate::Result<()> {
+        self.add_fallback_face(face.parse(data)?);
+        Ok(())
+    }
+
+    fn add_fallback_face(&mut self, face: Face<'a>) {
+        let index = self.next_face_index();
+        let face = self.families[0].make_face(index, face, false);
+        for font in &mut self.families {
+            font.faces.push(face.clone());
+        }
+    }
+
+    /// Uses a dedicated face for the given family, e.g. an upright text font
+    /// for `Family::Roman`. The face does not need a MATH table. Without a
+    /// dedicated face, a family uses the math alphanumerics of the math font
+    pub fn set_family_font(
+        &mut self,
+        family: Family,
+        data: &'a [u8],
+        face: FaceSelector,
+    ) -> crate::Result<()> {
+        self.set_family_face(family, face.parse(data)?);
+        Ok(())
+    }
+
+    fn set_family_face(&mut self, family: Family, face: Face<'a>) {
+        let index = self.next_face_index();
+        let face = self.families[0].make_face(index, face, true);
+        self.families[Self::family_index(family)].set_family_face(face);
+    }
+
+    fn family_index(family: Family) -> usize {
+        Family::ALL.iter().position(|&f| f == family).unwrap()
+    }
+}
+
+/// SAFETY: The returned slice must not outlive `data`
+unsafe fn extend_lifetime(data: &Arc<[u8]>) -> &'static [u8] {
+    &*Arc::as_ptr(data)
+}
+
+impl FontSet<'static> {
+    pub fn new_from_owned_font_data(
+        data: impl Into<Arc<[u8]>>,
+        face: FaceSelector,
+    ) -> crate::Result<Self> {
+        let data: Arc<[u8]> = data.into();
+
+        // SAFETY: The data lives on the heap and is kept alive by `owned_data` as
+        // long as the font exists. The face is never handed out, so the
+        // 'static lifetime cannot escape
+        let borrowed = unsafe { extend_lifetime(&data) };
+
+        let face = face.parse(borrowed)?;
+        Self::new_from_face(face, vec![data])
+    }
+
+    pub fn add_owned_fallback_font(
+        &mut self,
+        data: impl Into<Arc<[u8]>>,
+        face: FaceSelector,
+    ) -> crate::Result<()> {
+        let data: Arc<[u8]> = data.into();
+
+        // SAFETY: See `new_from_owned_font_data`
+        let borrowed = unsafe { extend_lifetime(&data) };
+
+        self.add_fallback_face(face.parse(borrowed)?);
+        self.owned_data.push(data);
+        Ok(())
+    }
+
+    pub fn set_owned_family_font(
+        &mut self,
+        family: Family,
+        data: impl Into<Arc<[u8]>>,
+        face: FaceSelector,
+    ) -> crate::Result<()> {
+        let data: Arc<[u8]> = data.into();
+
+        // SAFETY: See `new_from_owned_font_data`
+        let borrowed = unsafe { extend_lifetime(&data) };
+
+        self.set_family_face(family, face.parse(borrowed)?);
+        self.owned_data.push(data);
+        Ok(())
+    }
+
+    pub fn set_family_font_from_file(
+        &mut self,
+        family: Family,
+        path: impl AsRef<std::path::Path>,
+        face: FaceSelector,
+    ) -> crate::Result<()> {
+        let data = std::fs::read(path)?;
+        self.set_owned_family_font(family, data, face)
+    }
+
+    pub fn add_fallback_font_from_file(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+        face: FaceSelector,
+    ) -> crate::Result<()> {
+        let data = std::fs::read(path)?;
+        self.add_owned_fallback_font(data, face)
+    }
+
+    pub fn new_from_file(path: impl AsRef<std::path::Path>) -> crate::Result<Self> {
+        Self::new_from_file_with_face(path, FaceSelector::default())
+    }
+
+    pub fn new_from_file_with_face(
+        path: impl AsRef<std::path::Path>,
+        face: FaceSelector,
+    ) -> crate::Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::new_from_owned_font_data(data, face)
+    }
+}
+
+#[cfg(feature = "embedded-font")]
+impl FontSet<'static> {
+    pub fn new_embedded(font: EmbeddedFont) -> Self {
+        // The embedded fonts are known to be valid
+        Self::new_from_font_data(font.data()).unwrap()
+    }
+
+    /// The default embedded font. It is parsed on first use and shared by all
+    /// callers afterwards
+    pub fn shared_default() -> Arc<Self> {
+        static FONTS: OnceLock<Arc<FontSet<'static>>> = OnceLock::new();
+        FONTS
+            .get_or_init(|| Arc::new(Self::new_embedded(EmbeddedFont::default())))
+            .clone()
+    }
+}
+
+/// Produces glyphs of a `FontSet` for the renderer `R`. Creating a font
+/// backend from an existing font set is cheap; the font set is not copied
 pub struct FontBackend<'a, R: OpenTypeRenderer> {
-    font: Font<'a, R>,
+    /// One font per family, in the order of `Family::ALL`
+    fonts: Vec<Font<'a, R>>,
 }
 
+/// A font backend which owns its font data
+pub type OwnedFontBackend<R> = FontBackend<'static, R>;
+
 impl<'a, R: OpenTypeRenderer> common::FontBackend for FontBackend<'a, R> {
     type Glyph = Glyph<R>;
 
-    fn get_font(&self, _family: common::Family) -> &dyn common::Font<Self::Glyph> {
-        &self.font
+    fn get_font(&self, family: common::Family) -> &dyn common::Font<Self::Glyph> {
+        self.font(family)
     }
 }
 
 impl<'a, R: OpenTypeRenderer> FontBackend<'a, R> {
-    pub fn new_from_font_data(data: &'a [u8]) -> Option<Self> {
-        let face = ttf_parser::Face::parse(data, 0).ok()?;
-        let font = Font {
-            face,
-            _phantom: Default::default(),
-        };
-        Some(Self { font })
+    pub fn new(fonts: impl Into<Arc<FontSet<'a>>>) -> Self {
+        let fonts = fonts.into();
+        let cache = Arc::new(cache::GlyphCache::new(
+            cache::GlyphCache::<Glyph<R>>::DEFAULT_CAPACITY,
+        ));
+
+        let fonts = (0..Family::ALL.len())
+            .map(|family| Font {
+                fonts: fonts.clone(),
+                family,
+                cache: cache.clone(),
+            })
+            .collect();
+
+        Self { fonts }
+    }
+
+    pub fn new_from_font_data(data: &'a [u8]) -> crate::Result<Self> {
+        Ok(Self::new(FontSet::new_from_font_data(data)?))
+    }
+
+    pub fn new_from_collection(data: &'a [u8], face: FaceSelector) -> crate::Result<Self> {
+        Ok(Self::new(FontSet::new_from_collection(data, face)?))
+    }
+
+    /// The font set used by this backend. It can be used to create backends
+    /// for other renderers without parsing the font again
+    pub fn font_set(&self) -> &Arc<FontSet<'a>> {
+        &self.fonts[0].fonts
+    }
+
+    /// See `FontSet::is_degraded`
+    pub fn is_degraded(&self) -> bool {
+        self.font_set().is_degraded()
+    }
+
+    fn font(&self, family: Family) -> &Font<'a, R> {
+        &self.fonts[FontSet::family_index(family)]
+    }
+
+    /// Statistics of the glyph cache, which is shared by all families
+    pub fn cache_stats(&self) -> CacheStats {
+        self.fonts[0].cache.stats()
+    }
+
+    /// Limits the number of cached glyphs. A capacity of zero disables the cache
+    pub fn set_cache_capacity(&self, capacity: usize) {
+        self.fonts[0].cache.set_capacity(capacity);
+    }
+
+    pub fn clear_cache(&self) {
+        self.fonts[0].cache.clear();
+    }
+}
+
+impl<R: OpenTypeRenderer> FontBackend<'static, R> {
+    pub fn new_from_owned_font_data(
+        data: impl Into<Arc<[u8]>>,
+        face: FaceSelector,
+    ) -> crate::Result<Self> {
+        Ok(Self::new(FontSet::new_from_owned_font_data(data, face)?))
+    }
+
+    pub fn new_from_file(path: impl AsRef<std::path::Path>) -> crate::Result<Self> {
+        Ok(Self::new(FontSet::new_from_file(path)?))
+    }
+
+    pub fn new_from_file_with_face(
+        path: impl AsRef<std::path::Path>,
+        face: FaceSelector,
+    ) -> crate::Result<Self> {
+        Ok(Self::new(FontSet::new_from_file_with_face(path, face)?))
+    }
+}
+
+#[cfg(feature = "embedded-font")]
+impl<R: OpenTypeRenderer> FontBackend<'static, R> {
+    pub fn new_embedded(font: EmbeddedFont) -> Self {
+        Self::new(FontSet::new_embedded(font))
     }
 }
 
 #[cfg(feature = "embedded-font")]
 impl<R: OpenTypeRenderer> Default for FontBackend<'static, R> {
+    /// Uses the shared default font set, so the font is only parsed once
     fn default() -> Self {
-        Self::new_from_font_data(include_bytes!("../../data/NewCMMath-Regular.otf")).unwrap()
+        Self::new(FontSet::shared_default())
     }
 }
 
@@ -436,10 +1188,18 @@ impl<'a, R: OpenTypeRenderer, FB: std::borrow::Borrow<FontBackend<'a, R>>> commo
         y0: f32,
         color: Color,
     ) {
-        self.renderer.render_path(x0, y0, &glyph.path, color);
+        self.renderer.render_glyph(x0, y0, glyph, color);
     }
 
     fn render_box(&mut self, x0: f32, y0: f32, width: f32, height: f32) {
         self.renderer.render_box(x0, y0, width, height);
     }
+
+    fn begin_group(&mut self, atom_type: &crate::mathlist::AtomType) {
+        self.renderer.begin_group(atom_type);
+    }
+
+    fn end_group(&mut self) {
+        self.renderer.end_group();
+    }
 }
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+/// Statistics of the glyph cache of a font backend
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    /// Number of glyphs currently in the cache
+    pub len: usize,
+    pub capacity: usize,
+}
+
+/// Face index, glyph id and size (as bits of the `f32`)
+type Key = (u16, u16, u32);
+
+struct Entry<G> {
+    glyph: G,
+    last_used: u64,
+}
+
+struct Inner<G> {
+    entries: HashMap<Key, Entry<G>>,
+    stats: CacheStats,
+    /// Incremented on every access, used to find the least recently used glyphs
+    clock: u64,
+}
+
+/// Cache for glyphs (including their outlines), shared by all fonts of a
+/// backend
+pub(super) struct GlyphCache<G> {
+    inner: Mutex<Inner<G>>,
+}
+
+impl<G: Clone> GlyphCache<G> {
+    pub const DEFAULT_CAPACITY: usize = 4096;
+
+    pub fn new(capacity: usize) -> Self {
+        let stats = CacheStats {
+            capacity,
+            ..Default::default()
+        };
+
+        Self {
+            inner: Mutex::new(Inner {
+                entries: HashMap::new(),
+                stats,
+                clock: 0,
+            }),
+        }
+    }
+
+    /// Returns the cached glyph or creates it using `create`. Glyphs which
+    /// cannot be created are not cached
+    pub fn get_or_insert_with(
+        &self,
+        face: u16,
+        id: u16,
+        size: f32,
+        create: impl FnOnce() -> Option<G>,
+    ) -> Option<G> {
+        let key = (face, id, size.to_bits());
+
+        {
+            let mut inner = self.lock();
+            inner.clock += 1;
+            let clock = inner.clock;
+
+            if let Some(entry) = inner.entries.get_mut(&key) {
+                entry.last_used = clock;
+                let glyph = entry.glyph.clone();
+                inner.stats.hits += 1;
+                return Some(glyph);
+            }
+
+            inner.stats.misses += 1;
+        }
+
+        // The lock is not held while building the outline, so other threads
+        // are not blocked. At worst, a glyph is created twice
+        let glyph = create()?;
+
+        let mut inner = self.lock();
+        if inner.stats.capacity > 0 {
+            if inner.entries.len() >= inner.stats.capacity {
+                let capacity = inner.stats.capacity;
+                inner.evict(capacity / 2);
+            }
+
+            let last_used = inner.clock;
+            let entry = Entry {
+                glyph: glyph.clone(),
+                last_used,
+            };
+            inner.entries.insert(key, entry);
+            inner.stats.len = inner.entries.len();
+        }
+
+        Some(glyph)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    /// Changes the capacity. A capacity of zero disables the cache
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut inner = self.lock();
+        inner.stats.capacity = capacity;
+        let excess = inner.entries.len().saturating_sub(capacity);
+        if excess > 0 {
+            inner.evict(excess);
+        }
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        inner.entries.clear();
+        inner.stats.len = 0;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner<G>> {
+        // The cache is consistent even if a panic occurred while it was locked
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<G> Inner<G> {
+    /// Removes (at least one of) the least recently used glyphs
+    fn evict(&mut self, count: usize) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        let count = count.clamp(1, self.entries.len());
+
+        let mut last_used: Vec<u64> = self.entries.values().map(|e| e.last_used).collect();
+        let (_, &mut threshold, _) = last_used.select_nth_unstable(count - 1);
+
+        let before = self.entries.len();
+        self.entries.retain(|_, entry| entry.last_used > threshold);
+        self.stats.evictions += (before - self.entries.len()) as u64;
+        self.stats.len = self.entries.len();
+    }
+}
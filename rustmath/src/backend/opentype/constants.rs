@@ -0,0 +1,186 @@
+use ttf_parser::{math::MathValue, Face};
+
+/// Converts the values of the MATH table to `f32`
+trait ToUnits {
+    fn to_units(self) -> f32;
+}
+
+impl ToUnits for MathValue<'_> {
+    fn to_units(self) -> f32 {
+        self.value as f32
+    }
+}
+
+impl ToUnits for i16 {
+    fn to_units(self) -> f32 {
+        self as f32
+    }
+}
+
+impl ToUnits for u16 {
+    fn to_units(self) -> f32 {
+        self as f32
+    }
+}
+
+macro_rules! math_constants {
+    ($($name:ident,)*) => {
+        /// All constants of the OpenType MATH table, in font units (except for
+        /// the percentages). The names follow the OpenType specification.
+        /// For fonts without a MATH table, they are synthesized from the
+        /// general font metrics
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct MathConstants {
+            $(pub $name: f32,)*
+        }
+
+        impl MathConstants {
+            pub(super) fn from_table(constants: &ttf_parser::math::Constants) -> Self {
+                Self {
+                    $($name: constants.$name().to_units(),)*
+                }
+            }
+        }
+    };
+}
+
+math_constants! {
+    script_percent_scale_down,
+    script_script_percent_scale_down,
+    delimited_sub_formula_min_height,
+    display_operator_min_height,
+    math_leading,
+    axis_height,
+    accent_base_height,
+    flattened_accent_base_height,
+    subscript_shift_down,
+    subscript_top_max,
+    subscript_baseline_drop_min,
+    superscript_shift_up,
+    superscript_shift_up_cramped,
+    superscript_bottom_min,
+    superscript_baseline_drop_max,
+    sub_superscript_gap_min,
+    superscript_bottom_max_with_subscript,
+    space_after_script,
+    upper_limit_gap_min,
+    upper_limit_baseline_rise_min,
+    lower_limit_gap_min,
+    lower_limit_baseline_drop_min,
+    stack_top_shift_up,
+    stack_top_display_style_shift_up,
+    stack_bottom_shift_down,
+    stack_bottom_display_style_shift_down,
+    stack_gap_min,
+    stack_display_style_gap_min,
+    stretch_stack_top_shift_up,
+    stretch_stack_bottom_shift_down,
+    stretch_stack_gap_above_min,
+    stretch_stack_gap_below_min,
+    fraction_numerator_shift_up,
+    fraction_numerator_display_style_shift_up,
+    fraction_denominator_shift_down,
+    fraction_denominator_display_style_shift_down,
+    fraction_numerator_gap_min,
+    fraction_num_display_style_gap_min,
+    fraction_rule_thickness,
+    fraction_denominator_gap_min,
+    fraction_denom_display_style_gap_min,
+    skewed_fraction_horizontal_gap,
+    skewed_fraction_vertical_gap,
+    overbar_vertical_gap,
+    overbar_rule_thickness,
+    overbar_extra_ascender,
+    underbar_vertical_gap,
+    underbar_rule_thickness,
+    underbar_extra_descender,
+    radical_vertical_gap,
+    radical_display_style_vertical_gap,
+    radical_rule_thickness,
+    radical_extra_ascender,
+    radical_kern_before_degree,
+    radical_kern_after_degree,
+    radical_degree_bottom_raise_percent,
+}
+
+impl MathConstants {
+    /// Approximates the constants of a font without a MATH table. The ratios
+    /// are taken from Latin Modern Math, relative to its x-height, cap height
+    /// and rule thickness
+    pub(super) fn synthesize(face: &Face) -> Self {
+        let em = face.units_per_em() as f32;
+        let positive = |value: Option<i16>| value.filter(|&v| v > 0).map(|v| v as f32);
+
+        let x_height = positive(face.x_height()).unwrap_or(0.45 * em);
+        let cap_height = positive(face.capital_height()).unwrap_or(0.7 * em);
+        let rule = positive(face.underline_metrics().map(|m| m.thickness)).unwrap_or(0.04 * em);
+
+        // The math axis is the center of the plus sign. Without one, it is
+        // a little above half the x-height
+        let axis_height = face
+            .glyph_index('+')
+            .and_then(|id| face.glyph_bounding_box(id))
+            .map(|bbox| (bbox.y_min as f32 + bbox.y_max as f32) / 2.0)
+            .filter(|&axis| axis > 0.0)
+            .unwrap_or(0.58 * x_height);
+
+        Self {
+            script_percent_scale_down: 70.0,
+            script_script_percent_scale_down: 50.0,
+            delimited_sub_formula_min_height: 1.9 * cap_height,
+            display_operator_min_height: 1.9 * cap_height,
+            math_leading: 0.36 * x_height,
+            axis_height,
+            accent_base_height: x_height,
+            flattened_accent_base_height: cap_height,
+            subscript_shift_down: 0.57 * x_height,
+            subscript_top_max: 0.8 * x_height,
+            subscript_baseline_drop_min: 0.46 * x_height,
+            superscript_shift_up: 0.84 * x_height,
+            superscript_shift_up_cramped: 0.67 * x_height,
+            superscript_bottom_min: 0.25 * x_height,
+            superscript_baseline_drop_max: 0.58 * x_height,
+            sub_superscript_gap_min: 4.0 * rule,
+            superscript_bottom_max_with_subscript: 0.8 * x_height,
+            space_after_script: 0.13 * x_height,
+            upper_limit_gap_min: 5.0 * rule,
+            upper_limit_baseline_rise_min: 0.26 * x_height,
+            lower_limit_gap_min: 4.0 * rule,
+            lower_limit_baseline_drop_min: 0.88 * cap_height,
+            stack_top_shift_up: 1.03 * x_height,
+            stack_top_display_style_shift_up: cap_height,
+            stack_bottom_shift_down: 0.8 * x_height,
+            stack_bottom_display_style_shift_down: cap_height,
+            stack_gap_min: 3.0 * rule,
+            stack_display_style_gap_min: 7.0 * rule,
+            stretch_stack_top_shift_up: 0.26 * x_height,
+            stretch_stack_bottom_shift_down: 0.88 * cap_height,
+            stretch_stack_gap_above_min: 5.0 * rule,
+            stretch_stack_gap_below_min: 4.0 * rule,
+            fraction_numerator_shift_up: 0.91 * x_height,
+            fraction_numerator_display_style_shift_up: cap_height,
+            fraction_denominator_shift_down: 0.8 * x_height,
+            fraction_denominator_display_style_shift_down: cap_height,
+            fraction_numerator_gap_min: rule,
+            fraction_num_display_style_gap_min: 3.0 * rule,
+            fraction_rule_thickness: rule,
+            fraction_denominator_gap_min: rule,
+            fraction_denom_display_style_gap_min: 3.0 * rule,
+            skewed_fraction_horizontal_gap: 0.81 * x_height,
+            skewed_fraction_vertical_gap: 0.22 * x_height,
+            overbar_vertical_gap: 3.0 * rule,
+            overbar_rule_thickness: rule,
+            overbar_extra_ascender: rule,
+            underbar_vertical_gap: 3.0 * rule,
+            underbar_rule_thickness: rule,
+            underbar_extra_descender: rule,
+            radical_vertical_gap: 1.25 * rule,
+            radical_display_style_vertical_gap: rule + 0.25 * x_height,
+            radical_rule_thickness: rule,
+            radical_extra_ascender: rule,
+            radical_kern_before_degree: 5.0 / 18.0 * em,
+            radical_kern_after_degree: -10.0 / 18.0 * em,
+            radical_degree_bottom_raise_percent: 60.0,
+        }
+    }
+}
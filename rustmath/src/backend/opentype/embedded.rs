@@ -0,0 +1,49 @@
+#[cfg(not(any(
+    feature = "font-newcm-regular",
+    feature = "font-newcm-book",
+    feature = "font-latinmodern"
+)))]
+compile_error!("The `embedded-font` feature requires one of the `font-*` features");
+
+/// Fonts which can be included in the binary using the `font-*` features
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum EmbeddedFont {
+    /// New Computer Modern Math, regular weight (`font-newcm-regular`)
+    #[cfg(feature = "font-newcm-regular")]
+    NewCMRegular,
+    /// New Computer Modern Math, book weight (`font-newcm-book`)
+    #[cfg(feature = "font-newcm-book")]
+    NewCMBook,
+    /// Latin Modern Math (`font-latinmodern`)
+    #[cfg(feature = "font-latinmodern")]
+    LatinModern,
+}
+
+impl EmbeddedFont {
+    /// All fonts enabled by features
+    pub const ALL: &'static [Self] = &[
+        #[cfg(feature = "font-newcm-regular")]
+        Self::NewCMRegular,
+        #[cfg(feature = "font-newcm-book")]
+        Self::NewCMBook,
+        #[cfg(feature = "font-latinmodern")]
+        Self::LatinModern,
+    ];
+
+    pub fn data(self) -> &'static [u8] {
+        match self {
+            #[cfg(feature = "font-newcm-regular")]
+            Self::NewCMRegular => include_bytes!("../../../data/NewCMMath-Regular.otf"),
+            #[cfg(feature = "font-newcm-book")]
+            Self::NewCMBook => include_bytes!("../../../data/NewCMMath-Book.otf"),
+            #[cfg(feature = "font-latinmodern")]
+            Self::LatinModern => include_bytes!("../../../data/latinmodern-math.otf"),
+        }
+    }
+}
+
+impl Default for EmbeddedFont {
+    fn default() -> Self {
+        Self::ALL[0]
+    }
+}
@@ -0,0 +1,62 @@
+//! Font data and characters for embedding the faces into documents
+
+use super::{FontFace, FontSet};
+use std::collections::HashMap;
+use ttf_parser::GlyphId;
+
+impl FontFace<'_> {
+    /// Reverses the unicode subtables of the cmap. A glyph with several
+    /// characters gets the lowest of them
+    fn chars(&self) -> &HashMap<GlyphId, char> {
+        self.chars.get_or_init(|| {
+            let mut chars = HashMap::new();
+            let subtables = self
+                .face
+                .tables()
+                .cmap
+                .into_iter()
+                .flat_map(|cmap| cmap.subtables)
+                .filter(|subtable| subtable.is_unicode());
+            for subtable in subtables {
+                subtable.codepoints(|codepoint| {
+                    let (Some(id), Some(ch)) =
+                        (subtable.glyph_index(codepoint), char::from_u32(codepoint))
+                    else {
+                        return;
+                    };
+                    chars
+                        .entry(id)
+                        .and_modify(|old: &mut char| *old = (*old).min(ch))
+                        .or_insert(ch);
+                });
+            }
+            chars
+        })
+    }
+}
+
+impl<'a> FontSet<'a> {
+    fn face(&self, face: u16) -> Option<&FontFace<'a>> {
+        self.families
+            .iter()
+            .flat_map(|font| &font.faces)
+            .find(|font_face| font_face.index == face)
+    }
+
+    /// The font file of a face (see `Glyph::face`). Returns `None` for
+    /// unknown faces and for faces of font collections, which cannot be
+    /// embedded on their own
+    pub fn face_data(&self, face: u16) -> Option<&'a [u8]> {
+        let data = self.face(face)?.face.raw_face().data;
+        match ttf_parser::fonts_in_collection(data) {
+            Some(_) => None,
+            None => Some(data),
+        }
+    }
+
+    /// The character which the cmap of a face maps to a glyph. Script and
+    /// size variants have no character of their own
+    pub fn glyph_char(&self, face: u16, id: GlyphId) -> Option<char> {
+        self.face(face)?.chars().get(&id).copied()
+    }
+}
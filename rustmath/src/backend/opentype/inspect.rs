@@ -0,0 +1,163 @@
+//! Inspection of the contents of a math font, e.g. for debugging layout
+
+use super::{FontSet, MathConstants};
+use crate::common::Family;
+use ttf_parser::GlyphId;
+
+/// A size variant of a glyph. Sizes are in font units
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GlyphVariant {
+    pub glyph: GlyphId,
+    /// Height (vertical variants) or width (horizontal variants)
+    pub advance: u16,
+}
+
+/// A part of a glyph assembly. Sizes are in font units
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AssemblyPart {
+    pub glyph: GlyphId,
+    pub start_connector_length: u16,
+    pub end_connector_length: u16,
+    pub full_advance: u16,
+    pub extender: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GlyphAssembly {
+    pub italic_correction: i16,
+    pub parts: Vec<AssemblyPart>,
+}
+
+/// The variants and assembly of a glyph in one direction
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GlyphConstruction {
+    /// Increasing in size, starting with the glyph itself
+    pub variants: Vec<GlyphVariant>,
+    pub assembly: Option<GlyphAssembly>,
+}
+
+/// The size variants of a character, as used for delimiters, radicals,
+/// large operators and wide accents
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CharVariants {
+    /// Index of the face within the font set
+    pub face: u16,
+    pub glyph: GlyphId,
+    pub vertical: Option<GlyphConstruction>,
+    pub horizontal: Option<GlyphConstruction>,
+}
+
+/// Whether a command of the parser can be rendered using a font set
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommandCoverage {
+    pub command: &'static str,
+    pub ch: char,
+    /// Index of the face which provides the glyph, if any. Commands which the
+    /// parser does not accept as symbols are never supported
+    pub face: Option<u16>,
+}
+
+impl CommandCoverage {
+    pub fn is_supported(&self) -> bool {
+        self.face.is_some()
+    }
+}
+
+fn convert_construction(construction: ttf_parser::math::GlyphConstruction) -> GlyphConstruction {
+    let variants = construction
+        .variants
+        .into_iter()
+        .map(|variant| GlyphVariant {
+            glyph: variant.variant_glyph,
+            advance: variant.advance_measurement,
+        })
+        .collect();
+
+    let assembly = construction.assembly.map(|assembly| GlyphAssembly {
+        italic_correction: assembly.italics_correction.value,
+        parts: assembly
+            .parts
+            .into_iter()
+            .map(|part| AssemblyPart {
+                glyph: part.glyph_id,
+                start_connector_length: part.start_connector_length,
+                end_connector_length: part.end_connector_length,
+                full_advance: part.full_advance,
+                extender: part.part_flags.extender(),
+            })
+            .collect(),
+    });
+
+    GlyphConstruction { variants, assembly }
+}
+
+impl<'a> FontSet<'a> {
+    /// The MATH constants of the primary font. For degraded fonts (see
+    /// `is_degraded`), they are synthesized
+    pub fn math_constants(&self) -> &MathConstants {
+        &self.families[0].constants
+    }
+
+    /// The size variants and assemblies of a character. Returns `None` if no
+    /// face with a MATH table contains the character
+    pub fn char_variants(&self, ch: char) -> Option<CharVariants> {
+        let font = &self.families[Self::family_index(Family::Roman)];
+        let (face, variants, glyph) = font.find_variants(ch)?;
+
+        Some(CharVariants {
+            face: face.index,
+            glyph,
+            vertical: variants
+                .vertical_constructions
+                .get(glyph)
+                .map(convert_construction),
+            horizontal: variants
+                .horizontal_constructions
+                .get(glyph)
+                .map(convert_construction),
+        })
+    }
+
+    /// Returns the index of the face which provides the char in the given
+    /// family
+    pub fn find_char(&self, ch: char, family: Family) -> Option<u16> {
+        let font = &self.families[Self::family_index(family)];
+        font.find_glyph(ch).map(|(face, _)| face.index)
+    }
+
+    /// All characters contained in the faces used by the given family. The
+    /// letters of the math alphabets are listed as such, e.g. `𝑥`
+    pub fn supported_chars(&self, family: Family) -> Vec<char> {
+        let font = &self.families[Self::family_index(family)];
+        let mut chars: Vec<char> = font
+            .faces
+            .iter()
+            .flat_map(|face| face.face.tables().cmap)
+            .flat_map(|cmap| cmap.subtables)
+            .filter(|subtable| subtable.is_unicode())
+            .flat_map(|subtable| {
+                let mut codepoints = Vec::new();
+                subtable.codepoints(|codepoint| codepoints.push(codepoint));
+                codepoints
+            })
+            .filter_map(char::from_u32)
+            .collect();
+
+        chars.sort_unstable();
+        chars.dedup();
+        chars
+    }
+
+    /// Checks which of the single character commands of the parser (e.g.
+    /// `\infty`) can be rendered
+    pub fn command_coverage(&self) -> Vec<CommandCoverage> {
+        crate::parser::char_commands()
+            .map(|(command, ch)| CommandCoverage {
+                command,
+                ch,
+                face: crate::parser::symbol_family(ch)
+                    .and_then(|family| self.find_char(ch, family)),
+            })
+            .collect()
+    }
+}
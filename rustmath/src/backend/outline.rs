@@ -0,0 +1,111 @@
+//! Glyph outlines for PDF and PostScript, which only have cubic curves
+
+use super::number::Number;
+
+/// The names of the path construction operators, which differ between PDF
+/// (`m`, `l`, `c`, `h`) and PostScript (`moveto`, `lineto`, ...)
+pub trait PathOperators {
+    const MOVE_TO: &'static str;
+    const LINE_TO: &'static str;
+    const CURVE_TO: &'static str;
+    const CLOSE: &'static str;
+}
+
+enum PathOp {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    QuadTo(f32, f32, f32, f32),
+    CurveTo(f32, f32, f32, f32, f32, f32),
+    Close,
+}
+
+/// Builds a path from cubic curves only, for PDF and PostScript
+pub struct CubicOutlineBuilder<O> {
+    ops: Vec<PathOp>,
+    operators: core::marker::PhantomData<O>,
+}
+
+impl<O> Default for CubicOutlineBuilder<O> {
+    fn default() -> Self {
+        Self {
+            ops: Vec::new(),
+            operators: core::marker::PhantomData,
+        }
+    }
+}
+
+/// Writes the path using the operators of `O`. Quadratic curves are
+/// converted to cubic ones
+impl<O: PathOperators> super::opentype::OutlineBuilder<String> for CubicOutlineBuilder<O> {
+    fn finish(self, scale: f32) -> String {
+        use std::fmt::Write;
+
+        let n = |v: f32| Number(v * scale);
+        let mut result = String::new();
+        let mut current = (0.0, 0.0);
+        let mut start = (0.0, 0.0);
+
+        for op in self.ops {
+            let (x1, y1, x2, y2, x, y) = match op {
+                PathOp::MoveTo(x, y) => {
+                    current = (x, y);
+                    start = current;
+                    let _ = write!(result, "{} {} {} ", n(x), n(y), O::MOVE_TO);
+                    continue;
+                }
+                PathOp::LineTo(x, y) => {
+                    current = (x, y);
+                    let _ = write!(result, "{} {} {} ", n(x), n(y), O::LINE_TO);
+                    continue;
+                }
+                PathOp::Close => {
+                    current = start;
+                    let _ = write!(result, "{} ", O::CLOSE);
+                    continue;
+                }
+                PathOp::QuadTo(x1, y1, x, y) => {
+                    let (x0, y0) = current;
+                    let c1 = (x0 + 2.0 / 3.0 * (x1 - x0), y0 + 2.0 / 3.0 * (y1 - y0));
+                    let c2 = (x + 2.0 / 3.0 * (x1 - x), y + 2.0 / 3.0 * (y1 - y));
+                    (c1.0, c1.1, c2.0, c2.1, x, y)
+                }
+                PathOp::CurveTo(x1, y1, x2, y2, x, y) => (x1, y1, x2, y2, x, y),
+            };
+            current = (x, y);
+            let _ = write!(
+                result,
+                "{} {} {} {} {} {} {} ",
+                n(x1),
+                n(y1),
+                n(x2),
+                n(y2),
+                n(x),
+                n(y),
+                O::CURVE_TO
+            );
+        }
+        result
+    }
+}
+
+impl<O> ttf_parser::OutlineBuilder for CubicOutlineBuilder<O> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.ops.push(PathOp::MoveTo(x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.ops.push(PathOp::LineTo(x, y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.ops.push(PathOp::QuadTo(x1, y1, x, y));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.ops.push(PathOp::CurveTo(x1, y1, x2, y2, x, y));
+    }
+
+    fn close(&mut self) {
+        self.ops.push(PathOp::Close);
+    }
+}
@@ -0,0 +1,63 @@
+use super::{
+    opentype,
+    outline::{CubicOutlineBuilder, PathOperators},
+};
+use crate::{common::Color, options::Rgba, RenderOptions};
+
+mod document;
+mod metadata;
+
+pub use document::Document;
+pub use metadata::parse_metadata;
+
+/// The PDF path construction operators
+pub struct PdfOperators;
+
+impl PathOperators for PdfOperators {
+    const MOVE_TO: &'static str = "m";
+    const LINE_TO: &'static str = "l";
+    const CURVE_TO: &'static str = "c";
+    const CLOSE: &'static str = "h";
+}
+
+pub type OutlineBuilder = CubicOutlineBuilder<PdfOperators>;
+
+/// Renders into a single page vector PDF. Glyphs are drawn as filled paths
+#[derive(Clone)]
+pub struct PdfRenderer {
+    document: Document,
+    foreground: Rgba,
+    error_color: Rgba,
+}
+
+impl opentype::OpenTypeRenderer for PdfRenderer {
+    type Path = String;
+    type OutlineBuilder = OutlineBuilder;
+
+    type Image = Document;
+
+    fn new(width: f32, height: f32, options: &RenderOptions) -> crate::Result<Self> {
+        Ok(Self {
+            document: Document::new(width, height, options.background),
+            foreground: options.foreground,
+            error_color: options.error_color,
+        })
+    }
+
+    fn render_path(&mut self, x0: f32, y0: f32, path: &Self::Path, color: Color) {
+        let fill = match color {
+            Color::Normal => self.foreground,
+            Color::Error => self.error_color,
+        };
+        self.document.draw_path(x0, y0, path, fill);
+    }
+
+    fn render_box(&mut self, x0: f32, y0: f32, width: f32, height: f32) {
+        self.document
+            .draw_rect(x0, y0, width, height, self.foreground);
+    }
+
+    fn finish(self) -> Self::Image {
+        self.document
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{backend::number::Number, options::Rgba};
+use std::fmt::Write;
+
+/// A single page PDF document of the size of the formula
+#[derive(Clone)]
+pub struct Document {
+    width: f32,
+    height: f32,
+    /// The content stream of the page
+    content: String,
+    /// Opacities which need a graphics state, in the order of their names
+    opacities: Vec<u8>,
+    current_fill: Option<Rgba>,
+}
+
+impl Document {
+    /// A background of `None` means transparent
+    pub fn new(width: f32, height: f32, background: Option<Rgba>) -> Self {
+        let mut document = Self {
+            width,
+            height,
+            content: String::new(),
+            opacities: Vec::new(),
+            current_fill: None,
+        };
+
+        if let Some(background) = background {
+            document.draw_rect(0.0, 0.0, width, height, background);
+        }
+
+        document
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    fn set_fill(&mut self, color: Rgba) {
+        if self.current_fill == Some(color) {
+            return;
+        }
+        self.current_fill = Some(color);
+
+        let component = |c: u8| Number(c as f32 / 255.0);
+        let _ = writeln!(
+            self.content,
+            "{} {} {} rg",
+            component(color.red),
+            component(color.green),
+            component(color.blue)
+        );
+
+        let opacity = match self.opacities.iter().position(|&a| a == color.alpha) {
+            Some(idx) => idx,
+            None => {
+                self.opacities.push(color.alpha);
+                self.opacities.len() - 1
+            }
+        };
+        let _ = writeln!(self.content, "/GS{} gs", opacity);
+    }
+
+    pub fn draw_rect(&mut self, x0: f32, y0: f32, width: f32, height: f32, fill: Rgba) {
+        self.set_fill(fill);
+        let _ = writeln!(
+            self.content,
+            "{} {} {} {} re f",
+            Number(x0),
+            Number(y0),
+            Number(width),
+            Number(height)
+        );
+    }
+
+    /// Draws a path, given in PDF operators, at the given position
+    pub fn draw_path(&mut self, translate_x: f32, translate_y: f32, path: &str, fill: Rgba) {
+        if path.is_empty() {
+            return;
+        }
+
+        self.set_fill(fill);
+        let _ = writeln!(
+            self.content,
+            "q 1 0 0 1 {} {} cm {}f Q",
+            Number(translate_x),
+            Number(translate_y),
+            path
+        );
+    }
+
+    /// Writes the document. The metadata is stored in the document
+    /// information dictionary
+    pub fn write(&self, metadata: &[(&str, &str)]) -> Vec<u8> {
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_owned(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_owned(),
+        ];
+
+        let mut graphics_states = String::new();
+        for (idx, alpha) in self.opacities.iter().enumerate() {
+            let alpha = Number(*alpha as f32 / 255.0);
+            let _ = write!(graphics_states, " /GS{} << /ca {} >>", idx, alpha);
+        }
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /ExtGState <<{} >> >> /Contents 4 0 R >>",
+            Number(self.width),
+            Number(self.height),
+            graphics_states
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            self.content.len(),
+            self.content
+        ));
+
+        let mut info = String::from("<< /Producer ");
+        write_text_string("rustmath", &mut info);
+        for (key, value) in metadata {
+            let _ = write!(info, " /{} ", key);
+            write_text_string(value, &mut info);
+        }
+        info.push_str(" >>");
+        objects.push(info);
+
+        // The binary comment marks the file as binary for transfer programs
+        let mut out = Vec::new();
+        out.extend_from_slice(b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n");
+
+        let mut offsets = Vec::new();
+        for (idx, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", idx + 1, object).as_bytes());
+        }
+
+        let xref = out.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(trailer, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            trailer,
+            "trailer\n<< /Size {} /Root 1 0 R /Info {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            objects.len(),
+            xref
+        );
+        out.extend_from_slice(trailer.as_bytes());
+
+        out
+    }
+}
+
+/// Writes a text string as UTF-16BE hex string, which can contain any
+/// character and needs no escaping
+fn write_text_string(s: &str, out: &mut String) {
+    out.push_str("<FEFF");
+    for unit in s.encode_utf16() {
+        let _ = write!(out, "{:04X}", unit);
+    }
+    out.push('>');
+}
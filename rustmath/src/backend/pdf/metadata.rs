@@ -0,0 +1,180 @@
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_till, take_while},
+    character::complete::{char, digit1, multispace0},
+    combinator::{map, map_opt, map_res},
+    multi::many0,
+    sequence::{delimited, preceded, terminated},
+};
+
+type ParseResult<'a, T> = nom::IResult<&'a str, T>;
+
+/// Returns the entries of the document information dictionary which have
+/// string values
+pub fn parse_metadata(pdf: &[u8]) -> Option<Vec<(&str, String)>> {
+    if !pdf.starts_with(b"%PDF-") {
+        return None;
+    }
+
+    let trailer = rfind(pdf, b"trailer")?;
+    let trailer = core::str::from_utf8(&pdf[trailer..]).ok()?;
+    let (_, info) = info_reference(trailer).ok()?;
+
+    let header = format!("\n{} 0 obj", info);
+    let start = rfind(pdf, header.as_bytes())? + header.len();
+    let end = start + find(&pdf[start..], b"endobj")?;
+    let object = core::str::from_utf8(&pdf[start..end]).ok()?;
+
+    let (_, entries) = dictionary(object).ok()?;
+    Some(entries)
+}
+
+fn find(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len()).position(|w| w == needle)
+}
+
+fn rfind(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len()).rposition(|w| w == needle)
+}
+
+fn info_reference(src: &str) -> ParseResult<'_, u32> {
+    let (src, _) = take_till_tag("/Info")(src)?;
+    let (src, _) = tag("/Info")(src)?;
+    map_res(preceded(multispace0, digit1), str::parse)(src)
+}
+
+fn take_till_tag<'a>(needle: &'static str) -> impl Fn(&'a str) -> ParseResult<'a, &'a str> {
+    move |src: &'a str| {
+        let idx = src.find(needle).ok_or_else(|| {
+            nom::Err::Error(nom::error::make_error(
+                src,
+                nom::error::ErrorKind::TakeUntil,
+            ))
+        })?;
+        Ok((&src[idx..], &src[..idx]))
+    }
+}
+
+fn name(src: &str) -> ParseResult<'_, &str> {
+    preceded(
+        char('/'),
+        take_while(|ch: char| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-'),
+    )(src)
+}
+
+fn hex_string(src: &str) -> ParseResult<'_, String> {
+    map_opt(
+        delimited(char('<'), take_till(|ch| ch == '>'), char('>')),
+        decode_hex,
+    )(src)
+}
+
+fn decode_hex(hex: &str) -> Option<String> {
+    let digits: Vec<u8> = hex
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .map(|ch| ch.to_digit(16).map(|d| d as u8))
+        .collect::<Option<_>>()?;
+    let bytes: Vec<u8> = digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0))
+        .collect();
+    decode_text(&bytes)
+}
+
+/// Text strings are either UTF-16BE with byte order mark or (approximately)
+/// Latin-1
+fn decode_text(bytes: &[u8]) -> Option<String> {
+    match bytes.strip_prefix(&[0xFE, 0xFF]) {
+        Some(utf16) => {
+            let units: Vec<u16> = utf16
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16(&units).ok()
+        }
+        None => Some(bytes.iter().map(|&b| b as char).collect()),
+    }
+}
+
+fn literal_string(src: &str) -> ParseResult<'_, String> {
+    let (mut rest, _) = char('(')(src)?;
+    let mut bytes = Vec::new();
+    let mut depth = 0;
+
+    let mut chars = rest.char_indices();
+    while let Some((idx, ch)) = chars.next() {
+        match ch {
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                rest = &rest[idx + 1..];
+                let text = decode_text(&bytes).unwrap_or_default();
+                return Ok((rest, text));
+            }
+            ')' => depth -= 1,
+            '\\' => {
+                let escaped = match chars.next() {
+                    Some((_, 'n')) => '\n',
+                    Some((_, 'r')) => '\r',
+                    Some((_, 't')) => '\t',
+                    Some((_, ch)) => ch,
+                    None => break,
+                };
+                bytes.push(escaped as u8);
+                continue;
+            }
+            _ => {}
+        }
+        let mut buf = [0; 4];
+        bytes.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+    }
+
+    Err(nom::Err::Error(nom::error::make_error(
+        src,
+        nom::error::ErrorKind::Char,
+    )))
+}
+
+fn entry(src: &str) -> ParseResult<'_, (&str, Option<String>)> {
+    let (src, key) = preceded(multispace0, name)(src)?;
+    let (src, value) = preceded(
+        multispace0,
+        alt((
+            map(hex_string, Some),
+            map(literal_string, Some),
+            // Other values (names, numbers, references) are skipped
+            map(name, |_| None),
+            map(take_till(|ch| ch == '/' || ch == '>'), |_| None),
+        )),
+    )(src)?;
+    Ok((src, (key, value)))
+}
+
+fn dictionary(src: &str) -> ParseResult<'_, Vec<(&str, String)>> {
+    let (src, entries) = preceded(
+        preceded(multispace0, tag("<<")),
+        terminated(many0(entry), preceded(multispace0, tag(">>"))),
+    )(src)?;
+
+    let entries = entries
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value?)))
+        .collect();
+    Ok((src, entries))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_parse_dictionary() {
+        let src = "<< /Producer (rust\\(math\\)) /Type /Info /Title <FEFF00E4> /Ref 3 0 R >>";
+        let (_, entries) = super::dictionary(src).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                ("Producer", "rust(math)".to_owned()),
+                ("Title", "ä".to_owned())
+            ]
+        );
+    }
+}
@@ -1,15 +1,16 @@
 use super::opentype;
-use crate::common::Color;
+use crate::{common::Color, options::Rgba, RenderOptions};
 
 #[derive(Default)]
 pub struct OutlineBuilder {
     path_builder: tiny_skia::PathBuilder,
 }
 
-impl opentype::OutlineBuilder<tiny_skia::Path> for OutlineBuilder {
-    fn finish(self, scale: f32) -> tiny_skia::Path {
+/// Glyphs without outline (e.g. spaces) have no path
+impl opentype::OutlineBuilder<Option<tiny_skia::Path>> for OutlineBuilder {
+    fn finish(self, scale: f32) -> Option<tiny_skia::Path> {
         let ts = tiny_skia::Transform::from_scale(scale, scale);
-        self.path_builder.finish().unwrap().transform(ts).unwrap()
+        self.path_builder.finish()?.transform(ts)
     }
 }
 
@@ -35,59 +36,79 @@ impl ttf_parser::OutlineBuilder for OutlineBuilder {
     }
 }
 
+fn paint_from_rgba(color: Rgba) -> tiny_skia::Paint<'static> {
+    let mut paint = tiny_skia::Paint::default();
+    paint.set_color_rgba8(color.red, color.green, color.blue, color.alpha);
+    paint
+}
+
 #[derive(Clone)]
 pub struct TinySkiaRenderer {
     pixmap: tiny_skia::Pixmap,
+    scale: f32,
+    foreground: tiny_skia::Paint<'static>,
+    error: tiny_skia::Paint<'static>,
 }
 
 impl opentype::OpenTypeRenderer for TinySkiaRenderer {
-    type Path = tiny_skia::Path;
+    type Path = Option<tiny_skia::Path>;
     type OutlineBuilder = OutlineBuilder;
 
     type Image = tiny_skia::Pixmap;
 
-    fn new(width: f32, height: f32) -> Self {
-        const DPI: f32 = 96.0;
-        let scale = DPI / 72.0;
-        let pixmap = tiny_skia::Pixmap::new(
-            (width * scale).round() as u32,
-            (height * scale).round() as u32,
+    fn new(width: f32, height: f32, options: &RenderOptions) -> crate::Result<Self> {
+        // Even empty formulas result in a valid image
+        let scale = options.scale();
+        let mut pixmap = tiny_skia::Pixmap::new(
+            ((width * scale).round() as u32).max(1),
+            ((height * scale).round() as u32).max(1),
         )
-        .unwrap();
-
-        Self { pixmap }
+        .ok_or(crate::Error::ZeroSizeCanvas)?;
+
+        if let Some(background) = options.background {
+            pixmap.fill(tiny_skia::Color::from_rgba8(
+                background.red,
+                background.green,
+                background.blue,
+                background.alpha,
+            ));
+        }
+
+        Ok(Self {
+            pixmap,
+            scale,
+            foreground: paint_from_rgba(options.color(Color::Normal)),
+            error: paint_from_rgba(options.color(Color::Error)),
+        })
     }
 
     fn render_path(&mut self, x0: f32, y0: f32, path: &Self::Path, color: crate::common::Color) {
-        const DPI: f32 = 96.0;
-        let scale = DPI / 72.0;
-
-        let paint = {
-            let mut paint = tiny_skia::Paint::default();
-            match color {
-                Color::Normal => {}
-                Color::Error => paint.set_color_rgba8(255, 0, 0, 255),
-            }
-            paint
+        let Some(path) = path else {
+            return;
+        };
+        let scale = self.scale;
+
+        let paint = match color {
+            Color::Normal => &self.foreground,
+            Color::Error => &self.error,
         };
 
         let ts = tiny_skia::Transform::from_translate(x0, y0)
             .post_scale(scale, -scale)
             .post_translate(0.0, self.pixmap.height() as f32);
         self.pixmap
-            .fill_path(path, &paint, tiny_skia::FillRule::EvenOdd, ts, None);
+            .fill_path(path, paint, tiny_skia::FillRule::EvenOdd, ts, None);
     }
 
     fn render_box(&mut self, x0: f32, y0: f32, width: f32, height: f32) {
-        const DPI: f32 = 96.0;
-        let scale = DPI / 72.0;
+        let scale = self.scale;
 
         let ts = tiny_skia::Transform::from_scale(scale, -scale)
             .post_translate(0.0, self.pixmap.height() as f32);
 
-        let rect = tiny_skia::Rect::from_ltrb(x0, y0, x0 + width, y0 + height).unwrap();
-        self.pixmap
-            .fill_rect(rect, &tiny_skia::Paint::default(), ts, None);
+        if let Some(rect) = tiny_skia::Rect::from_ltrb(x0, y0, x0 + width, y0 + height) {
+            self.pixmap.fill_rect(rect, &self.foreground, ts, None);
+        }
     }
 
     fn finish(self) -> Self::Image {
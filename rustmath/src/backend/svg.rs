@@ -1,5 +1,6 @@
 use super::opentype;
-use crate::common::Color;
+use crate::{common::Color, options::Rgba, RenderOptions};
+use std::sync::Arc;
 
 mod svg_core;
 mod xml;
@@ -7,27 +8,73 @@ mod xml;
 #[derive(Clone)]
 pub struct SvgRenderer {
     image: svg_core::Image,
+    error_color: Rgba,
+    groups: bool,
+    text: bool,
+}
+
+impl SvgRenderer {
+    fn fill(&self, color: Color) -> Option<Rgba> {
+        match color {
+            Color::Normal => None,
+            Color::Error => Some(self.error_color),
+        }
+    }
 }
 
 impl opentype::OpenTypeRenderer for SvgRenderer {
-    type Path = String;
+    type Path = Arc<svg_core::Path>;
     type OutlineBuilder = svg_core::OutlineBuilder;
 
     type Image = svg_core::Image;
 
-    fn new(width: f32, height: f32) -> Self {
-        let image = svg_core::Image::new(width, height);
-        Self { image }
+    fn new(width: f32, height: f32, options: &RenderOptions) -> crate::Result<Self> {
+        let foreground = Some(options.foreground).filter(|&color| color != Rgba::BLACK);
+        let image = svg_core::Image::new(
+            width,
+            height,
+            foreground,
+            options.background,
+            options.svg_precision,
+        );
+        Ok(Self {
+            image,
+            error_color: options.error_color,
+            groups: options.svg_accessibility,
+            text: options.svg_text,
+        })
     }
 
     fn render_path(&mut self, x0: f32, y0: f32, path: &Self::Path, color: Color) {
-        self.image.draw_path(x0, y0, path.clone(), color);
+        let fill = self.fill(color);
+        self.image.draw_path(x0, y0, path.clone(), fill);
+    }
+
+    fn render_glyph(&mut self, x0: f32, y0: f32, glyph: &opentype::Glyph<Self>, color: Color) {
+        if self.text {
+            let fill = self.fill(color);
+            self.image.draw_glyph(x0, y0, glyph, fill);
+        } else {
+            self.render_path(x0, y0, &glyph.path, color);
+        }
     }
 
     fn render_box(&mut self, x0: f32, y0: f32, width: f32, height: f32) {
         self.image.draw_rect(x0, y0, width, height);
     }
 
+    fn begin_group(&mut self, atom_type: &crate::mathlist::AtomType) {
+        if self.groups {
+            self.image.begin_group(atom_type.name());
+        }
+    }
+
+    fn end_group(&mut self) {
+        if self.groups {
+            self.image.end_group();
+        }
+    }
+
     fn finish(self) -> Self::Image {
         self.image
     }
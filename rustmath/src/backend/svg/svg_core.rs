@@ -1,5 +1,23 @@
-use super::super::opentype;
+use super::super::{number::Rounded, opentype};
+use crate::options::Rgba;
+use opentype::ttf_parser::GlyphId;
+use std::collections::HashMap;
+use std::sync::Arc;
 
+/// Writes a coordinate, rounded to the given number of decimals
+#[derive(Clone, Copy)]
+struct Coord(f32, Option<usize>);
+
+impl core::fmt::Display for Coord {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.1 {
+            Some(precision) => Rounded(self.0, precision).fmt(f),
+            None => self.0.fmt(f),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
 enum PathOp {
     MoveTo(f32, f32),
     LineTo(f32, f32),
@@ -9,54 +27,75 @@ enum PathOp {
 }
 
 impl PathOp {
-    fn write(&self, scale: f32, out: &mut impl core::fmt::Write) -> core::fmt::Result {
+    fn scale(self, s: f32) -> Self {
         match self {
-            PathOp::MoveTo(x, y) => write!(out, "M {} {}", x * scale, y * scale),
-            PathOp::LineTo(x, y) => write!(out, "L {} {}", x * scale, y * scale),
-            PathOp::QuadTo(x1, y1, x2, y2) => write!(
-                out,
-                "Q {} {} {} {}",
-                x1 * scale,
-                y1 * scale,
-                x2 * scale,
-                y2 * scale
-            ),
+            PathOp::MoveTo(x, y) => PathOp::MoveTo(x * s, y * s),
+            PathOp::LineTo(x, y) => PathOp::LineTo(x * s, y * s),
+            PathOp::QuadTo(x1, y1, x2, y2) => PathOp::QuadTo(x1 * s, y1 * s, x2 * s, y2 * s),
             PathOp::CurveTo(x1, y1, x2, y2, x3, y3) => {
-                write!(
-                    out,
-                    "C {} {} {} {} {} {}",
-                    x1 * scale,
-                    y1 * scale,
-                    x2 * scale,
-                    y2 * scale,
-                    x3 * scale,
-                    y3 * scale
-                )
+                PathOp::CurveTo(x1 * s, y1 * s, x2 * s, y2 * s, x3 * s, y3 * s)
             }
+            PathOp::Close => PathOp::Close,
+        }
+    }
+
+    fn write(
+        &self,
+        precision: Option<usize>,
+        out: &mut impl core::fmt::Write,
+    ) -> core::fmt::Result {
+        let c = |v: f32| Coord(v, precision);
+        match *self {
+            PathOp::MoveTo(x, y) => write!(out, "M {} {}", c(x), c(y)),
+            PathOp::LineTo(x, y) => write!(out, "L {} {}", c(x), c(y)),
+            PathOp::QuadTo(x1, y1, x2, y2) => {
+                write!(out, "Q {} {} {} {}", c(x1), c(y1), c(x2), c(y2))
+            }
+            PathOp::CurveTo(x1, y1, x2, y2, x3, y3) => write!(
+                out,
+                "C {} {} {} {} {} {}",
+                c(x1),
+                c(y1),
+                c(x2),
+                c(y2),
+                c(x3),
+                c(y3)
+            ),
             PathOp::Close => out.write_char('z'),
         }
     }
 }
 
-#[derive(Default)]
-pub struct OutlineBuilder {
+/// The outline of a glyph, in pt
+pub struct Path {
     ops: Vec<PathOp>,
 }
 
-impl opentype::OutlineBuilder<String> for OutlineBuilder {
-    fn finish(self, scale: f32) -> String {
-        use std::fmt::Write;
-
-        let mut result = String::new();
-        let mut first = true;
-        for op in self.ops {
-            if first {
-                first = false;
-                let _ = result.write_char(' ');
+impl Path {
+    fn write(
+        &self,
+        precision: Option<usize>,
+        out: &mut impl core::fmt::Write,
+    ) -> core::fmt::Result {
+        for (idx, op) in self.ops.iter().enumerate() {
+            if idx > 0 {
+                out.write_char(' ')?;
             }
-            let _ = op.write(scale, &mut result);
+            op.write(precision, out)?;
         }
-        result
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct OutlineBuilder {
+    ops: Vec<PathOp>,
+}
+
+impl opentype::OutlineBuilder<Arc<Path>> for OutlineBuilder {
+    fn finish(self, scale: f32) -> Arc<Path> {
+        let ops = self.ops.into_iter().map(|op| op.scale(scale)).collect();
+        Arc::new(Path { ops })
     }
 }
 
@@ -82,39 +121,105 @@ impl ttf_parser::OutlineBuilder for OutlineBuilder {
     }
 }
 
-#[derive(Clone)]
-struct Translation(f32, f32);
+struct Translation(f32, f32, Option<usize>);
 
 impl core::fmt::Display for Translation {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let (x, y) = (Coord(self.0, self.2), Coord(self.1, self.2));
         if self.1 != 0. {
-            return write!(f, " transform=\"translate({}, {})\"", self.0, self.1);
+            return write!(f, " transform=\"translate({}, {})\"", x, y);
         } else if self.0 != 0. {
-            return write!(f, " transform=\"translate({})\"", self.0);
+            return write!(f, " transform=\"translate({})\"", x);
         }
         Ok(())
     }
 }
 
-#[derive(Clone)]
-struct Color(&'static str);
+#[derive(Clone, PartialEq)]
+struct Fill(Option<Rgba>);
 
-impl core::fmt::Display for Color {
+impl core::fmt::Display for Fill {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        if self.0.is_empty() {
+        let Some(color) = self.0 else {
             return Ok(());
+        };
+
+        write!(
+            f,
+            " fill=\"#{:02x}{:02x}{:02x}\"",
+            color.red, color.green, color.blue
+        )?;
+        if color.alpha != 255 {
+            write!(f, " fill-opacity=\"{}\"", color.alpha as f32 / 255.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// The 64-bit FNV-1a hash. Unlike `DefaultHasher`, its output does not
+/// change between Rust versions
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x0100_0000_01b3);
         }
-        write!(f, " fill=\"{}\"", self.0)
     }
+
+    /// Writes the length first, so that consecutive strings cannot be
+    /// confused, e.g. `ab` and `c` with `a` and `bc`
+    fn write_str(&mut self, s: &str) {
+        self.write(&(s.len() as u64).to_le_bytes());
+        self.write(s.as_bytes());
+    }
+}
+
+/// A glyph which is written as text if its face is embedded
+#[derive(Clone)]
+struct TextGlyph {
+    x: f32,
+    y: f32,
+    face: u16,
+    id: GlyphId,
+    size: f32,
+    path: Arc<Path>,
+    fill: Fill,
+}
+
+/// A face embedded as `@font-face`
+#[derive(Clone)]
+struct EmbeddedFace {
+    face: u16,
+    /// Base64 encoded font data
+    data: String,
+    /// The characters of the glyphs which are written as text
+    chars: HashMap<GlyphId, char>,
 }
 
-impl From<crate::common::Color> for Color {
-    fn from(value: crate::common::Color) -> Self {
-        match value {
-            crate::common::Color::Normal => Self(""),
-            crate::common::Color::Error => Self("red"),
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let byte = |idx: usize| chunk.get(idx).copied().unwrap_or(0);
+        let bits = u32::from_be_bytes([0, byte(0), byte(1), byte(2)]);
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                let value = (bits >> (18 - 6 * idx)) & 63;
+                result.push(ALPHABET[value as usize] as char);
+            } else {
+                result.push('=');
+            }
         }
     }
+    result
 }
 
 #[derive(Clone)]
@@ -125,42 +230,53 @@ enum Element {
         width: f32,
         height: f32,
     },
-    Path(Translation, String, Color),
-}
-
-impl Element {
-    pub fn write(&self, out: &mut impl core::fmt::Write) -> core::fmt::Result {
-        match self {
-            Element::Rect {
-                x0,
-                y0,
-                width,
-                height,
-            } => writeln!(
-                out,
-                "    <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" />",
-                x0, y0, width, height
-            ),
-            Element::Path(transform, data, color) => {
-                writeln!(out, "    <path{}{} d=\"{}\" />", transform, color, data)
-            }
-        }
-    }
+    Path {
+        x: f32,
+        y: f32,
+        path: Arc<Path>,
+        fill: Fill,
+    },
+    Text(TextGlyph),
+    /// A group of elements, annotated with the type of the atom
+    BeginGroup(&'static str),
+    EndGroup,
 }
 
 #[derive(Clone)]
 pub struct Image {
     width: f32,
     height: f32,
+    foreground: Option<Rgba>,
+    background: Option<Rgba>,
+    precision: Option<usize>,
+    /// Title and description for assistive technologies
+    label: Option<(String, String)>,
+    /// Prefix of the ids, derived from the content if `None`
+    id_prefix: Option<String>,
+    faces: Vec<EmbeddedFace>,
     elements: Vec<Element>,
 }
 
 impl Image {
-    pub fn new(width: f32, height: f32) -> Self {
+    /// A foreground of `None` means black, a background of `None` means
+    /// transparent. A precision of `None` writes coordinates unrounded
+    pub fn new(
+        width: f32,
+        height: f32,
+        foreground: Option<Rgba>,
+        background: Option<Rgba>,
+        precision: Option<usize>,
+    ) -> Self {
         let elements = Vec::new();
         Image {
             width,
             height,
+            foreground,
+            background,
+            precision,
+            label: None,
+            id_prefix: None,
+            faces: Vec::new(),
             elements,
         }
     }
@@ -174,18 +290,136 @@ impl Image {
         })
     }
 
-    pub fn draw_path(
+    pub fn draw_path(&mut self, x: f32, y: f32, path: Arc<Path>, fill: Option<Rgba>) {
+        if path.ops.is_empty() {
+            return;
+        }
+
+        self.elements.push(Element::Path {
+            x,
+            y,
+            path,
+            fill: Fill(fill),
+        });
+    }
+
+    /// Draws a glyph as text. Use `embed_fonts` to provide the font,
+    /// otherwise its outline is drawn
+    pub fn draw_glyph(
         &mut self,
-        translate_x: f32,
-        translate_y: f32,
-        data: String,
-        color: crate::common::Color,
+        x: f32,
+        y: f32,
+        glyph: &opentype::Glyph<super::SvgRenderer>,
+        fill: Option<Rgba>,
     ) {
-        self.elements.push(Element::Path(
-            Translation(translate_x, translate_y),
-            data,
-            color.into(),
-        ));
+        if glyph.path.ops.is_empty() {
+            return;
+        }
+
+        self.elements.push(Element::Text(TextGlyph {
+            x,
+            y,
+            face: glyph.face,
+            id: glyph.id,
+            size: glyph.size,
+            path: glyph.path.clone(),
+            fill: Fill(fill),
+        }));
+    }
+
+    /// Embeds each face used by the glyphs drawn as text. Only glyphs with
+    /// a character in the cmap of their face are written as text, the others
+    /// (e.g. script and size variants) and the glyphs of faces which cannot
+    /// be embedded are drawn as outlines
+    pub fn embed_fonts(&mut self, fonts: &opentype::FontSet) {
+        let mut faces: Vec<EmbeddedFace> = Vec::new();
+        for elem in &self.elements {
+            let Element::Text(glyph) = elem else {
+                continue;
+            };
+            let Some(ch) = fonts.glyph_char(glyph.face, glyph.id) else {
+                continue;
+            };
+            match faces
+                .iter_mut()
+                .find(|embedded| embedded.face == glyph.face)
+            {
+                Some(embedded) => {
+                    embedded.chars.insert(glyph.id, ch);
+                }
+                None => {
+                    if let Some(data) = fonts.face_data(glyph.face) {
+                        faces.push(EmbeddedFace {
+                            face: glyph.face,
+                            data: base64(data),
+                            chars: HashMap::from([(glyph.id, ch)]),
+                        });
+                    }
+                }
+            }
+        }
+        self.faces = faces;
+    }
+
+    /// The character to write for a glyph, `None` if it is drawn as outline
+    fn text_char(&self, glyph: &TextGlyph) -> Option<char> {
+        let face = self
+            .faces
+            .iter()
+            .find(|embedded| embedded.face == glyph.face)?;
+        face.chars.get(&glyph.id).copied()
+    }
+
+    /// The outline to draw for an element, if any
+    fn outline<'a>(&'a self, elem: &'a Element) -> Option<&'a Arc<Path>> {
+        match elem {
+            Element::Path { path, .. } => Some(path),
+            Element::Text(glyph) if self.text_char(glyph).is_none() => Some(&glyph.path),
+            _ => None,
+        }
+    }
+
+    pub fn begin_group(&mut self, atom_type: &'static str) {
+        self.elements.push(Element::BeginGroup(atom_type));
+    }
+
+    pub fn end_group(&mut self) {
+        self.elements.push(Element::EndGroup);
+    }
+
+    /// Labels the image with `role="img"`, a `<title>` and a `<desc>`
+    pub fn set_label(&mut self, title: &str, description: &str) {
+        self.label = Some((title.to_owned(), description.to_owned()));
+    }
+
+    /// Sets the prefix of the ids in the image, which must be a valid XML
+    /// name. Needed if the same formula is embedded several times into one
+    /// html document
+    pub fn set_id_prefix(&mut self, prefix: &str) {
+        self.id_prefix = Some(prefix.to_owned());
+    }
+
+    /// Writes each distinct outline once and returns the path data in the
+    /// order of the elements. Equal path data is shared, even if the glyphs
+    /// come from different faces
+    fn collect_paths(&self) -> (Vec<String>, Vec<usize>) {
+        let mut paths = Vec::new();
+        let mut indices = HashMap::new();
+        let mut references = Vec::new();
+
+        for elem in &self.elements {
+            if let Some(path) = self.outline(elem) {
+                let mut data = String::new();
+                let _ = path.write(self.precision, &mut data);
+                let idx = *indices.entry(data.clone()).or_insert_with(|| {
+                    paths.push(data);
+                    paths.len() - 1
+                });
+                references.push(idx);
+            }
+        }
+
+        (paths, references)
     }
 
     pub fn write(
@@ -193,25 +427,186 @@ impl Image {
         metadata: &[(&str, &str)],
         out: &mut impl core::fmt::Write,
     ) -> core::fmt::Result {
-        writeln!(
+        let (paths, references) = self.collect_paths();
+
+        // Ids must be unique if several images are embedded into the same
+        // html document, so by default they are derived from everything that
+        // is written. The hash is fixed, so that the same image always gets
+        // the same ids
+        let id_prefix = self.id_prefix.clone().unwrap_or_else(|| {
+            let mut hasher = Fnv::default();
+            for data in &paths {
+                hasher.write_str(data);
+            }
+            for idx in &references {
+                hasher.write(&(*idx as u32).to_le_bytes());
+            }
+            for elem in &self.elements {
+                if let Element::Text(glyph) = elem {
+                    hasher.write(&glyph.face.to_le_bytes());
+                    hasher.write(&glyph.id.0.to_le_bytes());
+                }
+            }
+            if let Some((title, description)) = &self.label {
+                hasher.write_str(title);
+                hasher.write_str(description);
+            }
+            for (meta_id, meta_value) in metadata {
+                hasher.write_str(meta_id);
+                hasher.write_str(meta_value);
+            }
+            format!("g{:016x}", hasher.0)
+        });
+
+        write!(
             out,
-            "<svg version=\"1.1\" width=\"{}pt\" height=\"{}pt\" viewBox=\"0 0 {} {}\" xmlns=\"http://www.w3.org/2000/svg\">",
+            "<svg version=\"1.1\" width=\"{}pt\" height=\"{}pt\" viewBox=\"0 0 {} {}\" xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\"",
             self.width, self.height, self.width, self.height
         )?;
+        if let Some((title, description)) = &self.label {
+            writeln!(
+                out,
+                " role=\"img\" aria-labelledby=\"{0}-title {0}-desc\">",
+                id_prefix
+            )?;
+            write!(out, "  <title id=\"{}-title\">", id_prefix)?;
+            super::xml::write_escaped_string(title, out)?;
+            writeln!(out, "</title>")?;
+            write!(out, "  <desc id=\"{}-desc\">", id_prefix)?;
+            super::xml::write_escaped_string(description, out)?;
+            writeln!(out, "</desc>")?;
+        } else {
+            writeln!(out, ">")?;
+        }
         for (meta_id, meta_value) in metadata {
             write!(out, "  <metadata id=\"{}\">", meta_id)?;
             super::xml::write_escaped_string(meta_value, out)?;
             writeln!(out, "</metadata>")?;
         }
+        if !paths.is_empty() || !self.faces.is_empty() {
+            writeln!(out, "  <defs>")?;
+            for face in &self.faces {
+                writeln!(
+                    out,
+                    "    <style>@font-face {{ font-family: \"{}-f{}\"; src: url(\"data:font/otf;base64,{}\") format(\"opentype\"); }}</style>",
+                    id_prefix, face.face, face.data
+                )?;
+            }
+            for (idx, data) in paths.iter().enumerate() {
+                writeln!(
+                    out,
+                    "    <path id=\"{}-{}\" d=\"{}\" />",
+                    id_prefix, idx, data
+                )?;
+            }
+            writeln!(out, "  </defs>")?;
+        }
+        if let Some(background) = self.background {
+            writeln!(
+                out,
+                "  <rect width=\"100%\" height=\"100%\"{} />",
+                Fill(Some(background))
+            )?;
+        }
         writeln!(
             out,
-            "  <g transform=\"translate(0.0,{}) scale(1.0,-1.0)\">",
-            self.height
+            "  <g transform=\"translate(0.0,{}) scale(1.0,-1.0)\"{}>",
+            self.height,
+            Fill(self.foreground)
         )?;
-        for elem in &self.elements {
-            elem.write(out)?;
+
+        let c = |v: f32| Coord(v, self.precision);
+        let mut references = references.into_iter();
+        let mut indent = 4;
+        let mut elements = self.elements.iter().peekable();
+        while let Some(elem) = elements.next() {
+            if let Element::EndGroup = elem {
+                indent -= 2;
+            }
+            write!(out, "{:1$}", "", indent)?;
+            match elem {
+                Element::Rect {
+                    x0,
+                    y0,
+                    width,
+                    height,
+                } => writeln!(
+                    out,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" />",
+                    c(*x0),
+                    c(*y0),
+                    c(*width),
+                    c(*height)
+                )?,
+                Element::Text(glyph) if self.text_char(glyph).is_some() => {
+                    // Consecutive glyphs of the same font share a text element
+                    let mut run = vec![glyph];
+                    while let Some(Element::Text(next)) = elements.next_if(|next| {
+                        matches!(next, Element::Text(next)
+                            if next.face == glyph.face && next.size == glyph.size && next.fill == glyph.fill
+                                && self.text_char(next).is_some())
+                    }) {
+                        run.push(next);
+                    }
+                    self.write_text(&id_prefix, &run, out)?
+                }
+                Element::Path { x, y, fill, .. } | Element::Text(TextGlyph { x, y, fill, .. }) => {
+                    let idx = references.next().unwrap_or_default();
+                    writeln!(
+                        out,
+                        "<use xlink:href=\"#{}-{}\"{}{} />",
+                        id_prefix,
+                        idx,
+                        Translation(*x, *y, self.precision),
+                        fill
+                    )?
+                }
+                Element::BeginGroup(atom_type) => {
+                    writeln!(out, "<g data-atom=\"{}\">", atom_type)?;
+                    indent += 2;
+                }
+                Element::EndGroup => writeln!(out, "</g>")?,
+            }
         }
         writeln!(out, "  </g>")?;
         writeln!(out, "</svg>")
     }
+
+    /// Writes glyphs of the same font as one text element, with a tspan per
+    /// baseline. The text is flipped back, as it is part of the flipped group.
+    /// The whole font is embedded, so ligatures are turned off to keep one
+    /// glyph per character
+    fn write_text(
+        &self,
+        id_prefix: &str,
+        run: &[&TextGlyph],
+        out: &mut impl core::fmt::Write,
+    ) -> core::fmt::Result {
+        let first = run[0];
+        let c = |v: f32| Coord(v, self.precision);
+        write!(
+            out,
+            "<text transform=\"scale(1, -1)\" font-family=\"{}-f{}\" font-size=\"{}\" style=\"font-variant-ligatures: none\"{}>",
+            id_prefix,
+            first.face,
+            c(first.size),
+            first.fill
+        )?;
+        for line in run.chunk_by(|a, b| a.y == b.y) {
+            out.write_str("<tspan x=\"")?;
+            for (idx, glyph) in line.iter().enumerate() {
+                if idx > 0 {
+                    out.write_char(' ')?;
+                }
+                write!(out, "{}", c(glyph.x))?;
+            }
+            write!(out, "\" y=\"{}\">", c(-line[0].y))?;
+            for glyph in line {
+                let ch = self.text_char(glyph).unwrap_or('?');
+                super::xml::write_escaped_string(ch.encode_utf8(&mut [0; 4]), out)?;
+            }
+            out.write_str("</tspan>")?;
+        }
+        writeln!(out, "</text>")
+    }
 }
@@ -23,7 +23,7 @@ pub fn write_escaped_string(s: &str, out: &mut impl core::fmt::Write) -> core::f
     Ok(())
 }
 
-pub fn parse_metadata<'a>(s: &'a str) -> Option<Vec<(&'a str, String)>> {
+pub fn parse_metadata(s: &str) -> Option<Vec<(&str, String)>> {
     let (_, result) = parse_metadata_imp(s).ok()?;
     Some(result)
 }
@@ -38,7 +38,9 @@ fn whitespace(src: &str) -> ParseResult<'_, ()> {
 
 fn parse_attr<'a>(src: &'a str) -> ParseResult<'a, (&'a str, &'a str)> {
     let (src, ()) = whitespace(src)?;
-    let (src, id) = nom::character::complete::alpha1(src)?;
+    let (src, id) = nom::bytes::complete::take_while1(|ch: char| {
+        ch.is_alphanumeric() || matches!(ch, '-' | '_' | ':')
+    })(src)?;
     let (src, _) =
         nom::sequence::delimited(whitespace, nom::character::complete::char('='), whitespace)(src)?;
 
@@ -102,9 +104,32 @@ fn parse_metadata_imp<'a>(src: &'a str) -> ParseResult<'a, Vec<(&'a str, String)
     let (src, ()) = skip_attrs(src)?;
     let (src, _) = nom::sequence::preceded(whitespace, nom::bytes::complete::tag(">"))(src)?;
 
+    // Accessible images start with a title and a description
+    let (src, _) = nom::multi::many0(label_tag)(src)?;
     nom::multi::many0(metadata_tag)(src)
 }
 
+fn label_tag(src: &str) -> ParseResult<'_, ()> {
+    let (src, _) = whitespace(src)?;
+    let (src, tag) = nom::sequence::preceded(
+        nom::character::complete::char('<'),
+        nom::branch::alt((
+            nom::bytes::complete::tag("title"),
+            nom::bytes::complete::tag("desc"),
+        )),
+    )(src)?;
+    let (src, ()) = skip_attrs(src)?;
+    let (src, _) = nom::bytes::complete::tag(">")(src)?;
+    let (src, _) = parse_escaped_string(src)?;
+    let (src, _) = nom::sequence::delimited(
+        nom::bytes::complete::tag("</"),
+        nom::bytes::complete::tag(tag),
+        nom::bytes::complete::tag(">"),
+    )(src)?;
+
+    Ok((src, ()))
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -114,6 +139,19 @@ mod tests {
         assert_eq!(src, "> 12");
     }
 
+    #[test]
+    fn test_parse_metadata_with_label() {
+        let source = concat!(
+            "<svg version=\"1.1\" xmlns=\"...\" role=\"img\" aria-labelledby=\"g1-title g1-desc\">\n",
+            "  <title id=\"g1-title\">x^2</title>\n",
+            "  <desc id=\"g1-desc\">x squared</desc>\n",
+            "  <metadata id=\"source\">rustmath</metadata>\n",
+        );
+
+        let metadata = super::parse_metadata(source).unwrap();
+        assert_eq!(metadata, [("source", "rustmath".to_owned())]);
+    }
+
     #[test]
     fn test_parse_metadata() {
         let source = concat!(
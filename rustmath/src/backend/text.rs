@@ -0,0 +1,423 @@
+//! Renders the layout as a grid of Unicode characters, e.g. for terminals.
+//! Every glyph occupies one cell, so the font parameters are given in cells:
+//! fractions take three rows (numerator, bar and denominator) and scripts are
+//! shifted by one row. Tall delimiters are assembled from the bracket pieces
+//! of the Miscellaneous Technical block (e.g. `⎛⎜⎝`)
+
+use crate::common::{self, font_params, Corner, Family, FontStyle};
+use crate::layout::Node;
+use std::collections::HashMap;
+
+/// The font size used for the translation. With one cell per em, a medium
+/// space (4/18 em) is about one cell wide
+pub const FONT_SIZE: f32 = 4.5;
+
+#[derive(Clone, Debug)]
+pub struct Glyph {
+    pub ch: char,
+    pub advance: f32,
+}
+
+impl common::Glyph for Glyph {
+    fn height(&self) -> f32 {
+        1.0
+    }
+    fn depth(&self) -> f32 {
+        0.0
+    }
+    fn advance(&self) -> f32 {
+        self.advance
+    }
+    fn italic_correction(&self) -> f32 {
+        0.0
+    }
+    fn ink_extent(&self) -> (f32, f32) {
+        (0.0, self.advance)
+    }
+    fn math_kern(&self, _corner: Corner, _height: f32) -> f32 {
+        0.0
+    }
+    fn set_advance(&mut self, advance: f32) {
+        self.advance = advance;
+    }
+}
+
+/// The pieces of vertical constructions, from bottom to top. Extenders are
+/// marked with `true`
+fn vertical_parts(ch: char) -> Option<&'static [(char, bool)]> {
+    let parts: &[_] = match ch {
+        '(' => &[('⎝', false), ('⎜', true), ('⎛', false)],
+        ')' => &[('⎠', false), ('⎟', true), ('⎞', false)],
+        '[' => &[('⎣', false), ('⎢', true), ('⎡', false)],
+        ']' => &[('⎦', false), ('⎥', true), ('⎤', false)],
+        '⌈' => &[('⎢', true), ('⎡', false)],
+        '⌉' => &[('⎥', true), ('⎤', false)],
+        '⌊' => &[('⎣', false), ('⎢', true)],
+        '⌋' => &[('⎦', false), ('⎥', true)],
+        '{' => &[
+            ('⎩', false),
+            ('⎪', true),
+            ('⎨', false),
+            ('⎪', true),
+            ('⎧', false),
+        ],
+        '}' => &[
+            ('⎭', false),
+            ('⎪', true),
+            ('⎬', false),
+            ('⎪', true),
+            ('⎫', false),
+        ],
+        '|' | '∣' => &[('│', true)],
+        '‖' | '∥' => &[('║', true)],
+        '∫' => &[('⌡', false), ('⎮', true), ('⌠', false)],
+        '√' => &[('⎷', false), ('│', true)],
+        '∑' => &[('⎳', false), ('⎲', false)],
+        _ => return None,
+    };
+    Some(parts)
+}
+
+/// A monospaced font with one cell per glyph. Size and style are ignored, as
+/// a terminal cannot make scripts smaller
+pub struct Font {
+    family: Family,
+}
+
+impl Font {
+    fn glyph(&self, ch: char) -> Glyph {
+        // Letters are upright in a terminal anyway, so the italic family keeps
+        // the plain characters
+        let ch = match self.family {
+            Family::Italic => ch,
+            family => family.math_alphanumeric(ch),
+        };
+        Glyph { ch, advance: 1.0 }
+    }
+}
+
+impl common::Font<Glyph> for Font {
+    fn get_fallback_glyph(&self, _size: f32, _style: FontStyle) -> Glyph {
+        self.glyph('?')
+    }
+
+    fn get_kerning(&self, _left: &Glyph, _right: &Glyph, _size: f32, _style: FontStyle) -> f32 {
+        0.0
+    }
+
+    fn get_glyph(&self, ch: char, _size: f32, _style: FontStyle) -> Option<Glyph> {
+        Some(self.glyph(ch))
+    }
+
+    fn get_larger_glyph(
+        &self,
+        _ch: char,
+        _size: f32,
+        _style: FontStyle,
+        _include_italic_correction: bool,
+    ) -> Option<Glyph> {
+        None
+    }
+
+    fn get_glyph_minwidth(
+        &self,
+        _ch: char,
+        _size: f32,
+        _style: FontStyle,
+        _min_width: f32,
+    ) -> Option<Glyph> {
+        None
+    }
+
+    fn get_glyph_minheight(
+        &self,
+        ch: char,
+        _size: f32,
+        _style: FontStyle,
+        min_height: f32,
+    ) -> Option<Glyph> {
+        (min_height <= 1.0).then(|| self.glyph(ch))
+    }
+
+    fn get_glyph_hor_construction(
+        &self,
+        _ch: char,
+        _size: f32,
+        _style: FontStyle,
+    ) -> Option<common::Construction<Glyph>> {
+        None
+    }
+
+    fn get_glyph_vert_construction(
+        &self,
+        ch: char,
+        _size: f32,
+        _style: FontStyle,
+    ) -> Option<common::Construction<Glyph>> {
+        let parts = vertical_parts(ch)?
+            .iter()
+            .map(
+                |&(ch, is_extender)| common::construction::ConstructionPart {
+                    glyph: Glyph { ch, advance: 1.0 },
+                    size: 1.0,
+                    max_start_overlap: 0.0,
+                    max_end_overlap: 0.0,
+                    is_extender,
+                },
+            )
+            .collect();
+
+        Some(common::Construction {
+            min_overlap: 0.0,
+            parts,
+        })
+    }
+
+    fn calculate_script_params(
+        &self,
+        _size: f32,
+        _style: FontStyle,
+        _cramped: bool,
+    ) -> font_params::ScriptParams {
+        // Scripts go to the row above or below, or next to the top and
+        // bottom row of a taller nucleus
+        font_params::ScriptParams {
+            subscript: font_params::SubScriptParams {
+                shift_down: 1.0,
+                top_max: 0.0,
+                baseline_drop_min: 0.0,
+            },
+            superscript: font_params::SuperScriptParams {
+                shift_up: 1.0,
+                bottom_min: 1.0,
+                baseline_drop_max: 1.0,
+            },
+            sub_super_gap_min: 0.0,
+            super_bottom_max_with_subscript: 1.0,
+        }
+    }
+
+    fn calculate_general_params(
+        &self,
+        _size: f32,
+        _style: FontStyle,
+        _cramped: bool,
+    ) -> font_params::GeneralParams {
+        // The middle of the baseline row, so that fraction bars are drawn in it
+        font_params::GeneralParams { axis_height: 0.5 }
+    }
+
+    fn calculate_fraction_params(
+        &self,
+        _size: f32,
+        _style: FontStyle,
+        _cramped: bool,
+    ) -> font_params::FractionParams {
+        let part = font_params::FractionPartParams {
+            shift: 1.0,
+            gap_min: 0.0,
+        };
+
+        font_params::FractionParams {
+            numerator: part.clone(),
+            denominator: part,
+            rule_thickness: 1.0,
+        }
+    }
+
+    fn calculate_limit_params(
+        &self,
+        _size: f32,
+        _style: FontStyle,
+        _cramped: bool,
+    ) -> font_params::LimitParams {
+        font_params::LimitParams {
+            upper_gap_min: 0.0,
+            upper_baseline_rise_min: 1.0,
+            lower_gap_min: 0.0,
+            lower_baseline_drop_min: 1.0,
+        }
+    }
+
+    fn calculate_radical_params(
+        &self,
+        _size: f32,
+        _style: FontStyle,
+        _cramped: bool,
+    ) -> font_params::RadicalParams {
+        // The bar takes the row above the radicand
+        font_params::RadicalParams {
+            vertical_gap: 0.0,
+            rule_thickness: 1.0,
+            extra_ascender: 0.0,
+        }
+    }
+}
+
+pub struct FontBackend {
+    fonts: [Font; 5],
+}
+
+impl Default for FontBackend {
+    fn default() -> Self {
+        Self {
+            fonts: Family::ALL.map(|family| Font { family }),
+        }
+    }
+}
+
+impl common::FontBackend for FontBackend {
+    type Glyph = Glyph;
+
+    fn get_font(&self, family: Family) -> &dyn common::Font<Glyph> {
+        let idx = Family::ALL.iter().position(|f| *f == family).unwrap_or(0);
+        &self.fonts[idx]
+    }
+}
+
+/// Characters placed at (row, column). Rows grow downwards, row 0 is the
+/// baseline of the formula
+#[derive(Default)]
+pub struct Grid {
+    cells: HashMap<(i32, i32), char>,
+}
+
+impl Grid {
+    /// Draws the node with its baseline in `row`, starting at `column`.
+    ///
+    /// Unlike `Node::render`, positions are rounded to whole cells per node:
+    /// with fractional spaces, absolute positions would drift, e.g. `a+b+c`
+    /// would be spaced unevenly
+    pub fn draw(&mut self, node: &Node<Glyph>, row: i32, column: i32) {
+        match node {
+            Node::Glue(_) => {}
+            Node::Glyph { glyph, .. } => {
+                self.cells.insert((row, column), glyph.ch);
+            }
+            Node::HBox { children, .. } => {
+                let mut column = column;
+                for (vshift, child) in children {
+                    self.draw(child, row - cells(*vshift), column);
+                    column += width(child);
+                }
+            }
+            Node::VBox { children, .. } => {
+                let content_width = content_width(children);
+
+                let mut y = 0.0;
+                let mut first = true;
+                for (hshift, child) in children {
+                    if first {
+                        first = false;
+                    } else {
+                        y += child.depth();
+                    }
+
+                    let row = row - cells(y);
+                    match (child, content_width) {
+                        (Node::Rule { height, depth, .. }, Some(width)) => {
+                            self.draw_rule(row, column, width, *height, *depth)
+                        }
+                        _ => self.draw(child, row, column + cells(*hshift)),
+                    }
+                    y += child.height(true);
+                }
+            }
+            Node::Rule {
+                height,
+                depth,
+                advance,
+            } => self.draw_rule(row, column, cells(*advance), *height, *depth),
+            Node::Group { child, .. } => self.draw(child, row, column),
+        }
+    }
+
+    fn draw_rule(&mut self, row: i32, column: i32, width: i32, height: f32, depth: f32) {
+        // The cells of a row reach from the baseline one unit upwards
+        let top = row + 1 - cells(height);
+        let bottom = (row + cells(depth)).max(top);
+        for row in top..=bottom {
+            for column in column..column + width {
+                self.cells.insert((row, column), '─');
+            }
+        }
+    }
+
+    /// The rows of the grid, without trailing spaces
+    pub fn lines(&self) -> Vec<String> {
+        let Some(first_row) = self.cells.keys().map(|(row, _)| *row).min() else {
+            return Vec::new();
+        };
+        let last_row = self
+            .cells
+            .keys()
+            .map(|(row, _)| *row)
+            .max()
+            .unwrap_or(first_row);
+        let first_column = self.cells.keys().map(|(_, column)| *column).min();
+        let first_column = first_column.unwrap_or(0);
+
+        (first_row..=last_row)
+            .map(|row| {
+                let mut columns: Vec<_> = self
+                    .cells
+                    .iter()
+                    .filter(|((r, _), _)| *r == row)
+                    .map(|((_, column), ch)| (*column, *ch))
+                    .collect();
+                columns.sort_unstable();
+
+                let mut line = String::new();
+                let mut next = first_column;
+                for (column, ch) in columns {
+                    let gap = (column - next).max(0) as usize;
+                    line.extend(std::iter::repeat_n(' ', gap));
+                    line.push(ch);
+                    next = column + 1;
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+/// Rounds a distance to whole cells. Halves are rounded up in both
+/// directions, so that parts centered on the axis (e.g. `⎲⎳`) stay adjacent
+fn cells(distance: f32) -> i32 {
+    (distance + 0.5).floor() as i32
+}
+
+/// Width of a node in cells, consistent with `Grid::draw`
+fn width(node: &Node<Glyph>) -> i32 {
+    match node {
+        Node::Glue(glue) => cells(*glue),
+        Node::Glyph { glyph, .. } => cells(glyph.advance),
+        Node::HBox { children, .. } => children.iter().map(|(_, child)| width(child)).sum(),
+        Node::VBox { children, .. } => content_width(children).unwrap_or_else(|| {
+            let widths = children
+                .iter()
+                .map(|(hshift, child)| cells(*hshift) + width(child));
+            widths.max().unwrap_or(0).max(0)
+        }),
+        Node::Rule { advance, .. } => cells(*advance),
+        Node::Group { child, .. } => width(child),
+    }
+}
+
+/// Width of the children of a vbox, except for rules and glue. Rules
+/// stretch over the content, like fraction bars
+fn content_width(children: &[(f32, Node<Glyph>)]) -> Option<i32> {
+    children
+        .iter()
+        .filter(|(_, child)| !matches!(child, Node::Rule { .. } | Node::Glue(_)))
+        .map(|(hshift, child)| cells(*hshift) + width(child))
+        .max()
+}
+
+/// Renders the layout as lines of text. Colors are ignored, so errors look
+/// like other glyphs
+pub fn render(node: &Node<Glyph>) -> String {
+    let mut grid = Grid::default();
+    grid.draw(node, 0, 0);
+    grid.lines().join("\n")
+}
@@ -3,16 +3,63 @@ pub mod font_params;
 
 pub use construction::Construction;
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum Color {
     Normal,
     Error,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
 pub enum Family {
     Roman,
     Italic,
+    Bold,
+    SansSerif,
+    Monospace,
+}
+
+impl Family {
+    pub const ALL: [Family; 5] = [
+        Family::Roman,
+        Family::Italic,
+        Family::Bold,
+        Family::SansSerif,
+        Family::Monospace,
+    ];
+
+    /// Maps ASCII letters and digits to the corresponding characters of the
+    /// Mathematical Alphanumeric Symbols block. Used when a math font has to
+    /// stand in for a family without a dedicated face
+    pub fn math_alphanumeric(self, ch: char) -> char {
+        let (upper, lower, digit) = match self {
+            Family::Roman => return ch,
+            Family::Italic if ch == 'h' => return 'ℎ',
+            Family::Italic => ('𝐴', '𝑎', None),
+            Family::Bold => ('𝐀', '𝐚', Some('𝟎')),
+            Family::SansSerif => ('𝖠', '𝖺', Some('𝟢')),
+            Family::Monospace => ('𝙰', '𝚊', Some('𝟶')),
+        };
+
+        let offset = |base: char, first: char| {
+            char::from_u32(base as u32 + ch as u32 - first as u32).unwrap_or(ch)
+        };
+
+        match ch {
+            'A'..='Z' => offset(upper, 'A'),
+            'a'..='z' => offset(lower, 'a'),
+            '0'..='9' => digit.map_or(ch, |digit| offset(digit, '0')),
+            _ => ch,
+        }
+    }
+}
+
+/// Corner of a glyph, used for math kerning
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Corner {
+    TopRight,
+    TopLeft,
+    BottomRight,
+    BottomLeft,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -29,9 +76,43 @@ pub trait Glyph: Clone {
     fn advance(&self) -> f32;
     fn italic_correction(&self) -> f32;
 
+    /// Horizontal extent of the ink (left, right), relative to the origin
+    fn ink_extent(&self) -> (f32, f32);
+
+    /// Horizontal kerning for a script attached at the given corner. `height`
+    /// is relative to the baseline of the glyph
+    fn math_kern(&self, corner: Corner, height: f32) -> f32;
+
     fn set_advance(&mut self, advance: f32);
 }
 
+/// A glyph without a font, for math lists which are never translated to a
+/// layout (e.g. MathML output)
+#[derive(Clone, Debug)]
+pub struct NoGlyph;
+
+impl Glyph for NoGlyph {
+    fn height(&self) -> f32 {
+        0.0
+    }
+    fn depth(&self) -> f32 {
+        0.0
+    }
+    fn advance(&self) -> f32 {
+        0.0
+    }
+    fn italic_correction(&self) -> f32 {
+        0.0
+    }
+    fn ink_extent(&self) -> (f32, f32) {
+        (0.0, 0.0)
+    }
+    fn math_kern(&self, _corner: Corner, _height: f32) -> f32 {
+        0.0
+    }
+    fn set_advance(&mut self, _advance: f32) {}
+}
+
 pub trait FontBackend {
     type Glyph: Glyph;
 
@@ -40,6 +121,10 @@ pub trait FontBackend {
 
 pub trait Font<G: Glyph> {
     fn get_fallback_glyph(&self, size: f32, style: FontStyle) -> G;
+
+    /// Kerning between two adjacent glyphs
+    fn get_kerning(&self, left: &G, right: &G, size: f32, style: FontStyle) -> f32;
+
     fn get_glyph(&self, ch: char, size: f32, style: FontStyle) -> Option<G>;
     fn get_larger_glyph(
         &self,
@@ -96,6 +181,20 @@ pub trait Font<G: Glyph> {
         style: FontStyle,
         cramped: bool,
     ) -> font_params::FractionParams;
+
+    fn calculate_limit_params(
+        &self,
+        size: f32,
+        style: FontStyle,
+        cramped: bool,
+    ) -> font_params::LimitParams;
+
+    fn calculate_radical_params(
+        &self,
+        size: f32,
+        style: FontStyle,
+        cramped: bool,
+    ) -> font_params::RadicalParams;
 }
 
 pub trait Renderer {
@@ -110,4 +209,8 @@ pub trait Renderer {
     );
 
     fn render_box(&mut self, x0: f32, y0: f32, width: f32, height: f32);
+
+    /// Called before and after the translation of an atom is rendered
+    fn begin_group(&mut self, _atom_type: &crate::mathlist::AtomType) {}
+    fn end_group(&mut self) {}
 }
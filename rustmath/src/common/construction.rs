@@ -37,7 +37,8 @@ impl<'a, G: Glyph> Iterator for PartIterator<'a, G> {
                         continue;
                     }
 
-                    self.replicate_current = self.extenders;
+                    // The part itself is the first of the copies
+                    self.replicate_current = self.extenders - 1;
                 }
             }
 
@@ -119,12 +120,16 @@ impl<G: Glyph> Construction<G> {
     }
 
     pub fn construct(&self, min_size: f32) -> (f32, SizedPartIterator<'_, G>) {
+        // Without extenders, we cannot get any larger than the minimal construction
+        let has_extenders = self.parts.iter().any(|part| part.is_extender);
+
         for extenders in 0u32..100000u32 {
             // We should not need more extenders than this
             let (min, max, len) = self.size_bounds(extenders);
             assert!(min <= max);
-            if max >= min_size {
-                let goal = min_size.max(min);
+            if max >= min_size || !has_extenders {
+                // Without extenders, the size may be out of reach
+                let goal = min_size.clamp(min, max);
                 let overlap_factor = if min == max {
                     0.0
                 } else {
@@ -159,3 +164,58 @@ impl<G: Glyph> Construction<G> {
         (min_size, max_size, len)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Construction, ConstructionPart};
+    use crate::common::NoGlyph;
+
+    /// Bottom, extender and top, one unit each and without overlap
+    fn construction() -> Construction<NoGlyph> {
+        let part = |is_extender| ConstructionPart {
+            glyph: NoGlyph,
+            size: 1.0,
+            max_start_overlap: 0.0,
+            max_end_overlap: 0.0,
+            is_extender,
+        };
+
+        Construction {
+            min_overlap: 0.0,
+            parts: vec![part(false), part(true), part(false)],
+        }
+    }
+
+    #[test]
+    fn test_extender_count() {
+        let construction = construction();
+        for extenders in 0..4 {
+            let parts = construction.iter_parts(extenders);
+            assert_eq!(parts.count(), 2 + extenders as usize);
+        }
+
+        let (size, parts) = construction.construct(3.0);
+        assert_eq!(size, 3.0);
+        assert_eq!(parts.count(), 3);
+    }
+
+    #[test]
+    fn test_without_extenders() {
+        let mut construction = construction();
+        construction.parts.remove(1);
+        for part in &mut construction.parts {
+            part.max_start_overlap = 0.5;
+            part.max_end_overlap = 0.5;
+        }
+
+        // Too large: the parts are placed without overlap
+        let (size, mut parts) = construction.construct(5.0);
+        assert_eq!(size, 2.0);
+        assert!(parts.all(|(overlap, _)| overlap == 0.0));
+
+        // Too small: the parts overlap as far as possible
+        let (size, parts) = construction.construct(1.0);
+        assert_eq!(size, 1.5);
+        assert_eq!(parts.map(|(overlap, _)| overlap).sum::<f32>(), 0.5);
+    }
+}
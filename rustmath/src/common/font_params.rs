@@ -45,3 +45,10 @@ pub struct FractionParams {
     pub denominator: FractionPartParams,
     pub rule_thickness: f32,
 }
+
+#[derive(Clone)]
+pub struct RadicalParams {
+    pub vertical_gap: f32,
+    pub rule_thickness: f32,
+    pub extra_ascender: f32,
+}
@@ -0,0 +1,80 @@
+#[derive(Debug)]
+pub enum Error {
+    /// The source could not be parsed. `position` is the byte offset
+    /// where parsing stopped
+    Parse {
+        position: usize,
+    },
+    /// The font data could not be parsed
+    FontParse(ttf_parser::FaceParsingError),
+    /// The font collection has no face with the given PostScript name
+    FaceNotFound(String),
+    /// A glyph required for rendering is not contained in the font
+    GlyphMissing(char),
+    /// The image would have a width or height of zero pixels
+    ZeroSizeCanvas,
+    /// Writing the textual output (e.g. svg) failed
+    Format(core::fmt::Error),
+    #[cfg(feature = "png")]
+    PngEncoding(png::EncodingError),
+    Io(std::io::Error),
+    /// The data does not contain rustmath source in its metadata
+    MetadataNotFound,
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Parse { position } => write!(f, "parse error at position {}", position),
+            Error::FontParse(err) => write!(f, "invalid font data: {}", err),
+            Error::FaceNotFound(name) => write!(f, "font collection has no face named {:?}", name),
+            Error::GlyphMissing(ch) => write!(f, "font does not contain a glyph for {:?}", ch),
+            Error::ZeroSizeCanvas => write!(f, "cannot create an image of size zero"),
+            Error::Format(err) => write!(f, "formatting error: {}", err),
+            #[cfg(feature = "png")]
+            Error::PngEncoding(err) => write!(f, "png encoding failed: {}", err),
+            Error::Io(err) => write!(f, "i/o error: {}", err),
+            Error::MetadataNotFound => write!(f, "no rustmath source found in metadata"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::FontParse(err) => Some(err),
+            Error::Format(err) => Some(err),
+            #[cfg(feature = "png")]
+            Error::PngEncoding(err) => Some(err),
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ttf_parser::FaceParsingError> for Error {
+    fn from(err: ttf_parser::FaceParsingError) -> Self {
+        Error::FontParse(err)
+    }
+}
+
+impl From<core::fmt::Error> for Error {
+    fn from(err: core::fmt::Error) -> Self {
+        Error::Format(err)
+    }
+}
+
+#[cfg(feature = "png")]
+impl From<png::EncodingError> for Error {
+    fn from(err: png::EncodingError) -> Self {
+        Error::PngEncoding(err)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
@@ -1,4 +1,5 @@
 use crate::common::{self, Color, Construction, Font, FontStyle};
+use crate::mathlist::AtomType;
 
 pub enum Node<Glyph: common::Glyph> {
     Glue(f32),
@@ -23,6 +24,11 @@ pub enum Node<Glyph: common::Glyph> {
         depth: f32,
         advance: f32,
     },
+    /// Marks the translation of an atom. Does not affect the layout
+    Group {
+        atom_type: AtomType,
+        child: Box<Self>,
+    },
 }
 
 impl<Glyph: common::Glyph> Node<Glyph> {
@@ -34,6 +40,7 @@ impl<Glyph: common::Glyph> Node<Glyph> {
             Node::HBox { height, .. } | Node::VBox { height, .. } | Node::Rule { height, .. } => {
                 *height
             }
+            Node::Group { child, .. } => child.height(vertical_mode),
         }
     }
 
@@ -44,6 +51,7 @@ impl<Glyph: common::Glyph> Node<Glyph> {
             Node::HBox { depth, .. } | Node::VBox { depth, .. } | Node::Rule { depth, .. } => {
                 *depth
             }
+            Node::Group { child, .. } => child.depth(),
         }
     }
 
@@ -55,6 +63,33 @@ impl<Glyph: common::Glyph> Node<Glyph> {
             Node::HBox { advance, .. }
             | Node::VBox { advance, .. }
             | Node::Rule { advance, .. } => *advance,
+            Node::Group { child, .. } => child.advance(vertical_mode),
+        }
+    }
+
+    /// The glyph at the left edge, if the node starts with a glyph on its baseline
+    pub fn first_glyph(&self) -> Option<&Glyph> {
+        match self {
+            Node::Glyph { glyph, .. } => Some(glyph),
+            Node::HBox { children, .. } => match children.first()? {
+                (vshift, child) if *vshift == 0.0 => child.first_glyph(),
+                _ => None,
+            },
+            Node::Group { child, .. } => child.first_glyph(),
+            _ => None,
+        }
+    }
+
+    /// The glyph at the right edge, if the node ends with a glyph on its baseline
+    pub fn last_glyph(&self) -> Option<&Glyph> {
+        match self {
+            Node::Glyph { glyph, .. } => Some(glyph),
+            Node::HBox { children, .. } => match children.last()? {
+                (vshift, child) if *vshift == 0.0 => child.last_glyph(),
+                _ => None,
+            },
+            Node::Group { child, .. } => child.last_glyph(),
+            _ => None,
         }
     }
 
@@ -173,9 +208,10 @@ impl<Glyph: common::Glyph> Node<Glyph> {
         size: f32,
         style: FontStyle,
         color: Color,
+        center_on_axis: bool,
     ) -> Option<Self> {
         // TODO: Correct 'cramped' param
-        let params = font.calculate_general_params(size, style.into(), false);
+        let params = font.calculate_general_params(size, style, false);
 
         let glyph = font.get_glyph_minheight(ch, size, style, min_height);
         if let Some(glyph) = glyph {
@@ -187,13 +223,106 @@ impl<Glyph: common::Glyph> Node<Glyph> {
             construction,
             min_height,
             color,
-            Some(params.axis_height),
+            center_on_axis.then_some(params.axis_height),
         );
         Some(Self::new_vbox(children))
     }
 }
 
+/// Bounding box of the ink, relative to the origin of a node
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct InkBounds {
+    pub left: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub top: f32,
+}
+
+impl InkBounds {
+    fn union(self, other: Self) -> Self {
+        Self {
+            left: self.left.min(other.left),
+            right: self.right.max(other.right),
+            bottom: self.bottom.min(other.bottom),
+            top: self.top.max(other.top),
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> f32 {
+        self.top - self.bottom
+    }
+}
+
 impl<Glyph: common::Glyph> Node<Glyph> {
+    /// Returns `None` if the node does not contain any ink
+    pub fn ink_bounds(&self) -> Option<InkBounds> {
+        let mut result = None;
+        self.collect_ink_bounds(0.0, 0.0, &mut result);
+        result
+    }
+
+    fn collect_ink_bounds(&self, x0: f32, y0: f32, result: &mut Option<InkBounds>) {
+        let bounds = match self {
+            Node::Glue(_) => return,
+            Node::Glyph { glyph, .. } => {
+                let (left, right) = glyph.ink_extent();
+                InkBounds {
+                    left: x0 + left,
+                    right: x0 + right,
+                    bottom: y0 - glyph.depth(),
+                    top: y0 + glyph.height(),
+                }
+            }
+            Node::HBox { children, .. } => {
+                let mut x = x0;
+
+                for (vshift, child) in children {
+                    child.collect_ink_bounds(x, y0 + vshift, result);
+                    x += child.advance(false);
+                }
+                return;
+            }
+            Node::VBox { children, .. } => {
+                let mut y = y0;
+                let mut first = true;
+
+                for (hshift, child) in children {
+                    if first {
+                        first = false;
+                    } else {
+                        y += child.depth();
+                    }
+                    child.collect_ink_bounds(x0 + hshift, y, result);
+                    y += child.height(true);
+                }
+                return;
+            }
+            Node::Group { child, .. } => {
+                child.collect_ink_bounds(x0, y0, result);
+                return;
+            }
+            Node::Rule {
+                height,
+                depth,
+                advance,
+            } => InkBounds {
+                left: x0,
+                right: x0 + advance,
+                bottom: y0 - depth,
+                top: y0 + height,
+            },
+        };
+
+        *result = Some(match result {
+            Some(current) => current.union(bounds),
+            None => bounds,
+        });
+    }
+
     pub fn render<B: common::FontBackend<Glyph = Glyph>, R: common::Renderer<FontBackend = B>>(
         &self,
         renderer: &mut R,
@@ -232,6 +361,11 @@ impl<Glyph: common::Glyph> Node<Glyph> {
             } => {
                 renderer.render_box(x0, y0 - depth, *advance, depth + height);
             }
+            Node::Group { atom_type, child } => {
+                renderer.begin_group(atom_type);
+                child.render(renderer, x0, y0);
+                renderer.end_group();
+            }
         }
     }
 }
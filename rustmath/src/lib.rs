@@ -1,44 +1,151 @@
 pub mod backend;
 pub mod common;
+pub mod error;
 pub mod layout;
 pub mod mathlist;
+pub mod options;
 pub mod parser;
 
+pub use error::{Error, Result};
+pub use options::RenderOptions;
+
 #[cfg(test)]
 mod tests;
 
+/// Defines the backend used by the functions without an explicit font. It
+/// shares the parsed default font (and its glyph cache) between all calls and
+/// threads
+#[cfg(feature = "embedded-font")]
+macro_rules! default_backend {
+    ($name:ident, $renderer:ty) => {
+        fn $name() -> &'static backend::opentype::FontBackend<'static, $renderer> {
+            static BACKEND: std::sync::OnceLock<backend::opentype::FontBackend<$renderer>> =
+                std::sync::OnceLock::new();
+            BACKEND.get_or_init(Default::default)
+        }
+    };
+}
+
+/// The metadata of an image. The syntax is only stored if it is not LaTeX,
+/// like in images of earlier versions
+#[cfg(any(feature = "png", feature = "svg", feature = "pdf", feature = "eps"))]
+fn metadata<'a>(
+    src: &'a str,
+    options: &RenderOptions,
+    degraded: bool,
+) -> Vec<(&'static str, &'a str)> {
+    let mut metadata = vec![("source", "rustmath"), ("rustmath_src", src)];
+    if options.syntax != parser::Syntax::Latex {
+        metadata.push(("rustmath_syntax", options.syntax.name()));
+    }
+    if degraded {
+        metadata.push(("rustmath_degraded", "true"));
+    }
+    metadata
+}
+
+/// Finds the source and its syntax in the metadata written by [`metadata`]
+#[cfg(any(feature = "png", feature = "svg", feature = "pdf", feature = "eps"))]
+fn source_from_metadata<'a>(
+    metadata: impl IntoIterator<Item = (&'a str, String)>,
+) -> Option<(String, parser::Syntax)> {
+    let mut source = None;
+    let mut rustmath_source = None;
+    let mut syntax = Some(parser::Syntax::Latex);
+
+    for (keyword, value) in metadata {
+        match keyword {
+            "source" => source = Some(value),
+            "rustmath_src" => rustmath_source = Some(value),
+            "rustmath_syntax" => syntax = parser::Syntax::from_name(&value),
+            _ => {}
+        }
+    }
+
+    let source = source?;
+
+    if &source != "rustmath" {
+        return None;
+    }
+
+    Some((rustmath_source?, syntax?))
+}
+
 pub fn render_layout<R: backend::opentype::OpenTypeRenderer>(
-    fb: backend::opentype::FontBackend<R>,
+    fb: &backend::opentype::FontBackend<R>,
     node: layout::Node<<backend::opentype::FontBackend<'_, R> as common::FontBackend>::Glyph>,
-) -> Option<R::Image> {
-    let x_padding = 10.0; // padding in pt
-    let y_padding = 5.0; // padding in pt
+    options: &RenderOptions,
+) -> Result<R::Image> {
+    let padding = &options.padding;
+
+    // Origin of the node, relative to the bottom left corner of the content
+    let (x0, y0, content_width, content_height) = match node.ink_bounds() {
+        Some(ink) if options.crop_to_ink => (-ink.left, -ink.bottom, ink.width(), ink.height()),
+        _ => (
+            0.0,
+            node.depth(),
+            node.advance(false),
+            node.height(false) + node.depth(),
+        ),
+    };
+
+    let width = content_width + padding.left + padding.right;
+    let height = content_height + padding.top + padding.bottom;
+
+    let mut canvas = R::new(width, height, options)?;
+    let mut renderer = backend::opentype::Renderer::new(&mut canvas, fb);
 
-    let width = node.advance(false) + 2.0 * x_padding;
-    let height = node.height(false) + node.depth() + 2.0 * y_padding;
+    node.render(&mut renderer, padding.left + x0, padding.bottom + y0);
+    Ok(canvas.finish())
+}
 
-    let mut canvas = R::new(width, height);
-    let mut renderer = backend::opentype::Renderer::new(&mut canvas, fb);
+#[cfg(all(feature = "tiny-skia", feature = "embedded-font"))]
+default_backend!(default_raster_backend, backend::raster::TinySkiaRenderer);
 
-    node.render(&mut renderer, x_padding, y_padding + node.depth());
-    Some(canvas.finish())
+#[cfg(all(feature = "tiny-skia", feature = "embedded-font"))]
+pub fn render_string(src: &str) -> Result<tiny_skia::Pixmap> {
+    render_string_with(src, &RenderOptions::default())
 }
 
-#[cfg(feature = "tiny-skia")]
-pub fn render_string(src: &str) -> Option<tiny_skia::Pixmap> {
-    use backend::raster::TinySkiaRenderer;
+#[cfg(all(feature = "tiny-skia", feature = "embedded-font"))]
+pub fn render_string_with(src: &str, options: &RenderOptions) -> Result<tiny_skia::Pixmap> {
+    render_string_with_font(src, default_raster_backend(), options)
+}
 
-    let list = parser::parse(src)?;
+#[cfg(feature = "tiny-skia")]
+pub fn render_string_with_font(
+    src: &str,
+    fb: &backend::opentype::FontBackend<backend::raster::TinySkiaRenderer>,
+    options: &RenderOptions,
+) -> Result<tiny_skia::Pixmap> {
+    let list = parser::parse_with_syntax(src, options.syntax)?;
+    let node = list.translate(fb, options.font_size, mathlist::Style::Display);
+
+    render_layout(fb, node, options)
+}
 
-    let fb = backend::opentype::FontBackend::<TinySkiaRenderer>::default();
-    let node = list.translate(&fb, 36.0, mathlist::Style::Display);
+#[cfg(all(feature = "png", feature = "embedded-font"))]
+pub fn encode_png(src: &str, include_meta_data: bool) -> Result<Vec<u8>> {
+    encode_png_with(src, include_meta_data, &RenderOptions::default())
+}
 
-    render_layout(fb, node)
+#[cfg(all(feature = "png", feature = "embedded-font"))]
+pub fn encode_png_with(
+    src: &str,
+    include_meta_data: bool,
+    options: &RenderOptions,
+) -> Result<Vec<u8>> {
+    encode_png_with_font(src, include_meta_data, default_raster_backend(), options)
 }
 
 #[cfg(feature = "png")]
-pub fn encode_png(src: &str, include_meta_data: bool) -> Option<Vec<u8>> {
-    let pixmap = render_string(src)?;
+pub fn encode_png_with_font(
+    src: &str,
+    include_meta_data: bool,
+    fb: &backend::opentype::FontBackend<backend::raster::TinySkiaRenderer>,
+    options: &RenderOptions,
+) -> Result<Vec<u8>> {
+    let pixmap = render_string_with_font(src, fb, options)?;
 
     let mut data = Vec::new();
     data.reserve_exact(pixmap.data().len());
@@ -57,22 +164,19 @@ pub fn encode_png(src: &str, include_meta_data: bool) -> Option<Vec<u8>> {
         encoder.set_color(png::ColorType::Rgba);
         encoder.set_depth(png::BitDepth::Eight);
         if include_meta_data {
-            encoder
-                .add_itxt_chunk("source".to_owned(), "rustmath".to_owned())
-                .ok()?;
-            encoder
-                .add_itxt_chunk("rustmath_src".to_owned(), src.to_owned())
-                .ok()?;
+            for (keyword, value) in metadata(src, options, fb.is_degraded()) {
+                encoder.add_itxt_chunk(keyword.to_owned(), value.to_owned())?;
+            }
         }
-        let mut writer = encoder.write_header().ok()?;
-        writer.write_image_data(&data).ok()?;
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&data)?;
     }
 
-    Some(result)
+    Ok(result)
 }
 
 #[cfg(feature = "png")]
-fn get_source_from_png_metadata(png: &[u8]) -> Option<String> {
+fn get_source_from_png_metadata(png: &[u8]) -> Option<(String, parser::Syntax)> {
     if !png.starts_with(b"\x89PNG") {
         return None;
     }
@@ -80,95 +184,242 @@ fn get_source_from_png_metadata(png: &[u8]) -> Option<String> {
     let decoder = png::Decoder::new(png);
     let reader = decoder.read_info().ok()?;
 
-    let mut source = None;
-    let mut rustmath_source = None;
+    let metadata = reader.info().utf8_text.iter().filter_map(|chunk| {
+        let text = chunk.get_text().ok()?;
+        Some((chunk.keyword.as_str(), text))
+    });
+    source_from_metadata(metadata)
+}
 
-    for text_chunk in &reader.info().utf8_text {
-        match &text_chunk.keyword as &str {
-            "source" => source = Some(text_chunk.get_text().ok()?),
-            "rustmath_src" => rustmath_source = Some(text_chunk.get_text().ok()?),
-            _ => {}
-        }
-    }
+#[cfg(all(feature = "png", feature = "embedded-font"))]
+pub fn save_png(
+    src: &str,
+    include_meta_data: bool,
+    filename: impl AsRef<std::path::Path>,
+) -> Result<()> {
+    save_png_with(src, include_meta_data, filename, &RenderOptions::default())
+}
 
-    let source = source?;
+#[cfg(all(feature = "png", feature = "embedded-font"))]
+pub fn save_png_with(
+    src: &str,
+    include_meta_data: bool,
+    filename: impl AsRef<std::path::Path>,
+    options: &RenderOptions,
+) -> Result<()> {
+    let data = encode_png_with(src, include_meta_data, options)?;
+    std::fs::write(filename, data)?;
+    Ok(())
+}
 
-    if &source != "rustmath" {
-        return None;
-    }
+#[cfg(all(feature = "svg", feature = "embedded-font"))]
+default_backend!(default_svg_backend, backend::svg::SvgRenderer);
 
-    rustmath_source
+#[cfg(all(feature = "svg", feature = "embedded-font"))]
+pub fn render_svg(src: &str, include_meta_data: bool) -> Result<String> {
+    render_svg_with(src, include_meta_data, &RenderOptions::default())
 }
 
-#[cfg(feature = "png")]
-pub fn save_png(src: &str, include_meta_data: bool, filename: &str) {
-    let data = encode_png(src, include_meta_data).unwrap();
-    std::fs::write(filename, data).unwrap();
+#[cfg(all(feature = "svg", feature = "embedded-font"))]
+pub fn render_svg_with(
+    src: &str,
+    include_meta_data: bool,
+    options: &RenderOptions,
+) -> Result<String> {
+    render_svg_with_font(src, include_meta_data, default_svg_backend(), options)
 }
 
 #[cfg(feature = "svg")]
-pub fn render_svg(src: &str, include_meta_data: bool) -> Option<String> {
-    use backend::svg::SvgRenderer;
-
-    let list = parser::parse(src)?;
-
-    let fb = backend::opentype::FontBackend::<SvgRenderer>::default();
-    let node = list.translate(&fb, 36.0, mathlist::Style::Display);
-
-    let image = render_layout(fb, node)?;
+pub fn render_svg_with_font(
+    src: &str,
+    include_meta_data: bool,
+    fb: &backend::opentype::FontBackend<backend::svg::SvgRenderer>,
+    options: &RenderOptions,
+) -> Result<String> {
+    let list = parser::parse_with_syntax(src, options.syntax)?;
+    let description = options
+        .svg_accessibility
+        .then(|| mathlist::speech::describe(&list));
+    let node = list.translate(fb, options.font_size, mathlist::Style::Display);
+
+    let mut image = render_layout(fb, node, options)?;
+    if let Some(description) = description {
+        image.set_label(src, &description);
+    }
+    if let Some(prefix) = &options.svg_id_prefix {
+        image.set_id_prefix(prefix);
+    }
+    if options.svg_text {
+        image.embed_fonts(fb.font_set());
+    }
 
     let mut result = String::new();
     if include_meta_data {
-        let metadata: &[(&str, &str)] = &[("source", "rustmath"), ("rustmath_src", src)];
-        image.write(metadata, &mut result).ok()?;
+        let metadata = metadata(src, options, fb.is_degraded());
+        image.write(&metadata, &mut result)?;
     } else {
-        image.write(&[], &mut result).ok()?;
+        image.write(&[], &mut result)?;
     }
 
-    Some(result)
+    Ok(result)
 }
 
 #[cfg(feature = "svg")]
-fn get_source_from_svg_metadata(png: &[u8]) -> Option<String> {
+fn get_source_from_svg_metadata(png: &[u8]) -> Option<(String, parser::Syntax)> {
     let s = core::str::from_utf8(png).ok()?;
     let metadata = backend::svg::parse_metadata(s)?;
+    source_from_metadata(metadata)
+}
 
-    let mut source = None;
-    let mut rustmath_source = None;
+#[cfg(all(feature = "pdf", feature = "embedded-font"))]
+default_backend!(default_pdf_backend, backend::pdf::PdfRenderer);
 
-    for (keyword, value) in metadata {
-        match keyword {
-            "source" => source = Some(value),
-            "rustmath_src" => rustmath_source = Some(value),
-            _ => {}
-        }
+#[cfg(all(feature = "pdf", feature = "embedded-font"))]
+pub fn render_pdf(src: &str, include_meta_data: bool) -> Result<Vec<u8>> {
+    render_pdf_with(src, include_meta_data, &RenderOptions::default())
+}
+
+#[cfg(all(feature = "pdf", feature = "embedded-font"))]
+pub fn render_pdf_with(
+    src: &str,
+    include_meta_data: bool,
+    options: &RenderOptions,
+) -> Result<Vec<u8>> {
+    render_pdf_with_font(src, include_meta_data, default_pdf_backend(), options)
+}
+
+#[cfg(feature = "pdf")]
+pub fn render_pdf_with_font(
+    src: &str,
+    include_meta_data: bool,
+    fb: &backend::opentype::FontBackend<backend::pdf::PdfRenderer>,
+    options: &RenderOptions,
+) -> Result<Vec<u8>> {
+    let list = parser::parse_with_syntax(src, options.syntax)?;
+    let node = list.translate(fb, options.font_size, mathlist::Style::Display);
+
+    let document = render_layout(fb, node, options)?;
+
+    if include_meta_data {
+        let metadata = metadata(src, options, fb.is_degraded());
+        Ok(document.write(&metadata))
+    } else {
+        Ok(document.write(&[]))
     }
+}
 
-    let source = source?;
+#[cfg(feature = "pdf")]
+fn get_source_from_pdf_metadata(pdf: &[u8]) -> Option<(String, parser::Syntax)> {
+    let metadata = backend::pdf::parse_metadata(pdf)?;
+    source_from_metadata(metadata)
+}
 
-    if &source != "rustmath" {
-        return None;
+#[cfg(all(feature = "eps", feature = "embedded-font"))]
+default_backend!(default_eps_backend, backend::eps::EpsRenderer);
+
+#[cfg(all(feature = "eps", feature = "embedded-font"))]
+pub fn render_eps(src: &str, include_meta_data: bool) -> Result<String> {
+    render_eps_with(src, include_meta_data, &RenderOptions::default())
+}
+
+#[cfg(all(feature = "eps", feature = "embedded-font"))]
+pub fn render_eps_with(
+    src: &str,
+    include_meta_data: bool,
+    options: &RenderOptions,
+) -> Result<String> {
+    render_eps_with_font(src, include_meta_data, default_eps_backend(), options)
+}
+
+#[cfg(feature = "eps")]
+pub fn render_eps_with_font(
+    src: &str,
+    include_meta_data: bool,
+    fb: &backend::opentype::FontBackend<backend::eps::EpsRenderer>,
+    options: &RenderOptions,
+) -> Result<String> {
+    let list = parser::parse_with_syntax(src, options.syntax)?;
+    let node = list.translate(fb, options.font_size, mathlist::Style::Display);
+
+    let document = render_layout(fb, node, options)?;
+
+    let mut result = String::new();
+    if include_meta_data {
+        let metadata = metadata(src, options, fb.is_degraded());
+        document.write(&metadata, &mut result)?;
+    } else {
+        document.write(&[], &mut result)?;
     }
 
-    rustmath_source
+    Ok(result)
+}
+
+#[cfg(feature = "eps")]
+fn get_source_from_eps_metadata(eps: &[u8]) -> Option<(String, parser::Syntax)> {
+    let s = core::str::from_utf8(eps).ok()?;
+    let metadata = backend::eps::parse_metadata(s)?;
+    source_from_metadata(metadata)
+}
+
+/// Converts the formula to MathML, with the source as `application/x-tex`
+/// annotation. Needs no font, layout is done by the browser
+pub fn render_mathml(src: &str) -> Result<String> {
+    render_mathml_with(src, parser::Syntax::Latex)
+}
+
+/// Converts the formula to MathML. Sources other than MathML are kept as
+/// annotation
+pub fn render_mathml_with(src: &str, syntax: parser::Syntax) -> Result<String> {
+    let list = parser::parse_with_syntax::<common::NoGlyph>(src, syntax)?;
+    let annotation = syntax.annotation_encoding().map(|encoding| (encoding, src));
+
+    let mut result = String::new();
+    mathlist::mathml::write(&list, annotation, &mut result)?;
+    Ok(result)
+}
+
+/// Renders the formula as Unicode text for terminals, e.g. fractions on three
+/// lines. Needs no font
+pub fn render_text(src: &str) -> Result<String> {
+    render_text_with(src, parser::Syntax::Latex)
 }
 
-pub fn get_source_from_metadata(_data: &[u8]) -> Option<String> {
+pub fn render_text_with(src: &str, syntax: parser::Syntax) -> Result<String> {
+    let list = parser::parse_with_syntax(src, syntax)?;
+    let backend = backend::text::FontBackend::default();
+    let node = list.translate(&backend, backend::text::FONT_SIZE, mathlist::Style::Display);
+    Ok(backend::text::render(&node))
+}
+
+/// The source and its syntax stored in the metadata of an image
+pub fn get_source_from_metadata(_data: &[u8]) -> Result<(String, parser::Syntax)> {
     #[cfg(feature = "png")]
     {
-        let result = get_source_from_png_metadata(_data);
-        if result.is_some() {
-            return result;
+        if let Some(result) = get_source_from_png_metadata(_data) {
+            return Ok(result);
         }
     }
 
     #[cfg(feature = "svg")]
     {
-        let result = get_source_from_svg_metadata(_data);
-        if result.is_some() {
-            return result;
+        if let Some(result) = get_source_from_svg_metadata(_data) {
+            return Ok(result);
+        }
+    }
+
+    #[cfg(feature = "pdf")]
+    {
+        if let Some(result) = get_source_from_pdf_metadata(_data) {
+            return Ok(result);
+        }
+    }
+
+    #[cfg(feature = "eps")]
+    {
+        if let Some(result) = get_source_from_eps_metadata(_data) {
+            return Ok(result);
         }
     }
 
-    None
+    Err(Error::MetadataNotFound)
 }
@@ -2,6 +2,9 @@ fn main() {
     #[cfg(feature = "png")]
     {
         let src = "\\int\\sum x+\\mathop{log}y=2\\mitalpha";
-        rustmath::save_png(src, true, "image.png");
+        if let Err(err) = rustmath::save_png(src, true, "image.png") {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
     }
 }
@@ -2,6 +2,8 @@ use crate::common::{self, Color};
 use common::Family;
 
 mod layout_helper;
+pub mod mathml;
+pub mod speech;
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Style {
@@ -46,13 +48,13 @@ impl Style {
     }
 
     pub fn is_cramped(self) -> bool {
-        match self {
+        matches!(
+            self,
             Self::DisplayCramped
-            | Self::TextCramped
-            | Self::ScriptCramped
-            | Self::SuperScriptCramped => true,
-            _ => false,
-        }
+                | Self::TextCramped
+                | Self::ScriptCramped
+                | Self::SuperScriptCramped
+        )
     }
 
     pub fn to_numerator(self) -> Self {
@@ -71,13 +73,13 @@ impl Style {
     }
 }
 
-impl Into<common::FontStyle> for Style {
-    fn into(self) -> common::FontStyle {
-        if self <= Self::SuperScript {
+impl From<Style> for common::FontStyle {
+    fn from(style: Style) -> Self {
+        if style <= Style::SuperScript {
             common::FontStyle::SuperScript
-        } else if self <= Self::Script {
+        } else if style <= Style::Script {
             common::FontStyle::Script
-        } else if self <= Self::Text {
+        } else if style <= Style::Text {
             common::FontStyle::Text
         } else {
             common::FontStyle::Display
@@ -139,6 +141,18 @@ pub struct Atom<Glyph: common::Glyph> {
     pub superscript: Field<Glyph>,
 }
 
+impl<Glyph: common::Glyph> Atom<Glyph> {
+    /// An atom without scripts
+    pub fn new(atom_type: AtomType, nucleus: Field<Glyph>) -> Self {
+        Self {
+            atom_type,
+            nucleus,
+            subscript: Field::Empty,
+            superscript: Field::Empty,
+        }
+    }
+}
+
 pub struct Delimiter {
     pub ch: char,
     pub color: Color,
@@ -157,6 +171,21 @@ impl Delimiter {
         let half_height = half_height.max(content.depth() + params.axis_height);
 
         let min_height = 2.0 * half_height.max(0.0);
+        self.translate_min_height(font, min_height, size, style, true)
+    }
+
+    /// The delimiter with at least the given total height, or at its normal
+    /// size if the font has no larger one. Assembled delimiters are centered
+    /// on the axis if `center_on_axis` is set, otherwise they start at the
+    /// baseline
+    pub fn translate_min_height<G: common::Glyph>(
+        &self,
+        font: &dyn crate::common::Font<G>,
+        min_height: f32,
+        size: f32,
+        style: Style,
+        center_on_axis: bool,
+    ) -> crate::layout::Node<G> {
         let result = crate::layout::Node::new_extended_glyph_vert(
             font,
             self.ch,
@@ -164,7 +193,17 @@ impl Delimiter {
             size,
             style.into(),
             self.color,
+            center_on_axis,
         );
+        // Fonts without size variants (e.g. text fonts without a MATH table)
+        // at least get the delimiter at its normal size
+        let result = result.or_else(|| {
+            let glyph = font.get_glyph(self.ch, size, style.into())?;
+            Some(crate::layout::Node::Glyph {
+                glyph,
+                color: self.color,
+            })
+        });
         result.unwrap_or_else(|| {
             let glyph = font.get_fallback_glyph(size, style.into());
             crate::layout::Node::Glyph {
@@ -177,10 +216,14 @@ impl Delimiter {
 
 pub enum Field<Glyph: common::Glyph> {
     Empty,
-    Symbol(Color, char),
+    Symbol(Color, Family, char),
     Fallback(Color),
     MathList(Option<Delimiter>, MathList<Glyph>, Option<Delimiter>),
     Fraction(Box<Self>, Box<Self>),
+    /// A square root of the field
+    Radical(Box<Self>),
+    /// Rows of cells, e.g. of a matrix
+    Table(Vec<Vec<Self>>),
     Layout {
         translation: crate::layout::Node<Glyph>,
         italic_correction: f32,
@@ -191,6 +234,29 @@ impl<G: common::Glyph> Field<G> {
     pub fn is_empty(&self) -> bool {
         matches!(self, Self::Empty)
     }
+
+    pub fn is_italic_symbol(&self) -> bool {
+        matches!(self, Self::Symbol(_, Family::Italic, _))
+    }
+
+    /// Changes the family of all symbols within the field (e.g. `\mathbf`)
+    pub fn set_family(&mut self, family: Family) {
+        match self {
+            Field::Symbol(_, symbol_family, _) => *symbol_family = family,
+            Field::MathList(_, list, _) => list.set_family(family),
+            Field::Fraction(num, denom) => {
+                num.set_family(family);
+                denom.set_family(family);
+            }
+            Field::Radical(radicand) => radicand.set_family(family),
+            Field::Table(rows) => {
+                for cell in rows.iter_mut().flatten() {
+                    cell.set_family(family);
+                }
+            }
+            Field::Empty | Field::Fallback(_) | Field::Layout { .. } => {}
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -207,12 +273,27 @@ pub enum AtomType {
 }
 
 impl AtomType {
+    /// The lowercase name of the atom type, e.g. `ord`
+    pub fn name(&self) -> &'static str {
+        match self {
+            AtomType::Acc => "acc",
+            AtomType::Bin => "bin",
+            AtomType::Close => "close",
+            AtomType::Inner => "inner",
+            AtomType::Op => "op",
+            AtomType::Open => "open",
+            AtomType::Ord => "ord",
+            AtomType::Punct => "punct",
+            AtomType::Rel => "rel",
+        }
+    }
+
     /// Some atoms might precede a binary operator, some don't
     pub fn might_precede_bin(&self) -> bool {
-        match &self {
-            AtomType::Bin | AtomType::Rel | AtomType::Open | AtomType::Punct => false,
-            _ => true,
-        }
+        !matches!(
+            self,
+            AtomType::Bin | AtomType::Rel | AtomType::Open | AtomType::Punct
+        )
     }
 }
 
@@ -236,18 +317,30 @@ impl<Glyph: common::Glyph> Builder<Glyph> {
     }
 
     pub fn add_symbol(&mut self, ch: char, color: Color) {
-        let nucleus = Field::Symbol(color, ch);
-        let atom = Atom {
-            atom_type: AtomType::Ord,
-            nucleus,
-            subscript: Field::Empty,
-            superscript: Field::Empty,
-        };
-        self.add_atom(atom);
+        let nucleus = Field::Symbol(color, Family::Roman, ch);
+        self.add_atom(Atom::new(AtomType::Ord, nucleus));
     }
 }
 
 impl<Glyph: common::Glyph> MathList<Glyph> {
+    /// Number of atoms
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn set_family(&mut self, family: Family) {
+        for node in &mut self.0 {
+            let Node::Atom(atom) = node;
+            atom.nucleus.set_family(family);
+            atom.subscript.set_family(family);
+            atom.superscript.set_family(family);
+        }
+    }
+
     /// Translate into boxes
     pub fn translate<B: common::FontBackend<Glyph = Glyph>>(
         self,
@@ -272,48 +365,111 @@ impl<Glyph: common::Glyph> MathList<Glyph> {
 
         // TODO: Other passes?
 
+        let is_big =
+            |atom: &Atom<Glyph>| matches!(&atom.atom_type, AtomType::Op if style > Style::Text);
+
+        // Rule 17: Italic correction is added after italic symbols, unless
+        // they are followed by another italic symbol
+        let is_italic: Vec<bool> = list
+            .iter()
+            .map(|node| {
+                let Node::Atom(atom) = node;
+                atom.nucleus.is_italic_symbol()
+            })
+            .collect();
+
+        // Rule 14: Kerning between adjacent Ord symbols, inserted before the atom.
+        // As in TeX, it is added to the italic correction of the left symbol.
+        // A symbol with scripts is not kerned with the next one: the scripts
+        // are placed between the two glyphs
+        let mut kerns = vec![0.0f32; list.len()];
+        let mut previous_symbol = None::<(Family, Glyph)>;
+
         // Translate the nucleus for all atoms which have not been translated yet
-        for node in &mut list {
+        for (idx, node) in list.iter_mut().enumerate() {
             let Node::Atom(atom) = node;
-            let big = match &atom.atom_type {
-                AtomType::Op if style > Style::Text => true,
-                _ => false,
-            };
+            let has_scripts = !atom.subscript.is_empty() || !atom.superscript.is_empty();
+            let want_italic_correction = !has_scripts && !is_italic.get(idx + 1).unwrap_or(&false);
 
-            // TODO: Implement Rule 14 from the TeX-book
-            // This means dealing with kerning, ligatures
+            let symbol_family = match (&atom.atom_type, &atom.nucleus) {
+                (AtomType::Ord, Field::Symbol(_, family, _)) => Some(*family),
+                _ => None,
+            };
 
-            // TODO: Determine if we need italic correction (See Rule 17)
-            let italic_correction = false;
+            // Large operators are translated once the following atom is known
+            if !is_big(atom) {
+                atom.nucleus.translate(
+                    backend,
+                    size,
+                    style,
+                    None,
+                    !atom.subscript.is_empty(),
+                    want_italic_correction,
+                );
+            }
 
-            atom.nucleus.translate(
-                backend,
-                size,
-                style,
-                big,
-                !atom.subscript.is_empty(),
-                italic_correction,
-            );
+            // With italic correction, the glyph is followed by a glue
+            let symbol = symbol_family.and_then(|family| {
+                let glyph = atom.nucleus.translation()?.first_glyph()?;
+                Some((family, glyph.clone()))
+            });
+            if let (Some((family, left)), Some((_, right))) = (&previous_symbol, &symbol) {
+                let font = backend.get_font(*family);
+                kerns[idx] = font.get_kerning(left, right, size, style.into());
+            }
+            previous_symbol = symbol.filter(|_| !has_scripts);
 
             atom.subscript
-                .translate(backend, size, style.to_subscript(), false, false, true);
+                .translate(backend, size, style.to_subscript(), None, false, true);
             atom.superscript
-                .translate(backend, size, style.to_superscript(), false, false, true);
+                .translate(backend, size, style.to_superscript(), None, false, true);
+        }
+
+        // Large operators with a vertical assembly (e.g. integrals) grow with
+        // the following atom. Like delimiters, they are centered on the axis
+        let axis_height = backend
+            .get_font(Family::Italic)
+            .calculate_general_params(size, style.into(), style.is_cramped())
+            .axis_height;
+        for idx in 0..list.len() {
+            let min_height = match list.get(idx + 1) {
+                Some(Node::Atom(next)) => next.nucleus.translation().map_or(0.0, |next| {
+                    let half_height = next.height(false) - axis_height;
+                    2.0 * half_height.max(next.depth() + axis_height).max(0.0)
+                }),
+                None => 0.0,
+            };
+
+            let Node::Atom(atom) = &mut list[idx];
+            if is_big(atom) {
+                atom.nucleus.translate(
+                    backend,
+                    size,
+                    style,
+                    Some(min_height),
+                    !atom.subscript.is_empty(),
+                    false,
+                );
+            }
         }
 
         let mut nodes = Vec::new();
         nodes.reserve_exact(list.len() * 2);
         {
             let mut previous_atom_type = None::<AtomType>;
-            for node in &mut list {
+            for (node, kern) in list.iter_mut().zip(kerns) {
                 let Node::Atom(atom) = node;
                 let atom_type = &atom.atom_type;
 
+                if kern != 0.0 {
+                    nodes.push((0.0, crate::layout::Node::Glue(kern)));
+                }
+
                 if let Some(previous_atom_type) = &previous_atom_type {
                     let is_script = style <= Style::Script;
                     let spacing = spacing(
                         previous_atom_type,
-                        &atom_type,
+                        atom_type,
                         is_script,
                         thin_space,
                         med_space,
@@ -347,8 +503,17 @@ impl<Glyph: common::Glyph> MathList<Glyph> {
                         &superscript,
                     );
 
+                let (subscript_kern, superscript_kern) = layout_helper::calculate_script_kerns(
+                    &nucleus,
+                    &subscript,
+                    &superscript,
+                    subscript_vshift,
+                    superscript_vshift,
+                );
+
+                let mut atom_nodes = Vec::new();
                 if let Some(atom) = nucleus {
-                    nodes.push((0.0, atom));
+                    atom_nodes.push((0.0, atom));
                 }
 
                 let mut script_nodes = Vec::new();
@@ -356,7 +521,7 @@ impl<Glyph: common::Glyph> MathList<Glyph> {
 
                 if let Some(script) = subscript {
                     superscript_vshift -= subscript_vshift + script.height(false);
-                    script_nodes.push((0.0, script));
+                    script_nodes.push((subscript_kern, script));
                     vshift = subscript_vshift;
                 }
 
@@ -364,13 +529,23 @@ impl<Glyph: common::Glyph> MathList<Glyph> {
                     if !script_nodes.is_empty() && superscript_vshift > 0.0 {
                         script_nodes.push((0.0, crate::layout::Node::Glue(superscript_vshift)));
                     }
-                    script_nodes.push((italic_correction, script));
+                    script_nodes.push((italic_correction + superscript_kern, script));
                 }
 
                 if !script_nodes.is_empty() {
                     let vbox = crate::layout::Node::new_vbox(script_nodes);
-                    nodes.push((vshift, vbox));
+                    atom_nodes.push((vshift, vbox));
                 }
+
+                let child = match atom_nodes.len() {
+                    1 if atom_nodes[0].0 == 0.0 => atom_nodes.pop().unwrap().1,
+                    _ => crate::layout::Node::new_hbox(atom_nodes),
+                };
+                let group = crate::layout::Node::Group {
+                    atom_type: atom.atom_type.clone(),
+                    child: Box::new(child),
+                };
+                nodes.push((0.0, group));
             }
         }
 
@@ -384,19 +559,46 @@ impl<Glyph: common::Glyph> Field<Glyph> {
         backend: &B,
         size: f32,
         style: Style,
-        big: bool,
+        // For large operators: the height of the content the operator should cover
+        big: Option<f32>,
         has_subscript: bool,
-        _want_italic_correction: bool,
+        want_italic_correction: bool,
     ) {
         match self {
-            Field::Symbol(color, ch) => {
-                let font = backend.get_font(Family::Italic);
+            Field::Symbol(color, family, ch) => {
+                let font = backend.get_font(*family);
 
                 let glyph = big
-                    .then_some(())
                     .and_then(|_| font.get_larger_glyph(*ch, size, style.into(), !has_subscript))
                     .or_else(|| font.get_glyph(*ch, size, style.into()));
 
+                let min_height = big.unwrap_or(0.0);
+                let too_small = |glyph: &Glyph| glyph.height() + glyph.depth() < min_height;
+                if big.is_some() && glyph.as_ref().is_some_and(too_small) {
+                    let has_assembly = font
+                        .get_glyph_vert_construction(*ch, size, style.into())
+                        .is_some();
+                    let translation = has_assembly.then_some(()).and_then(|_| {
+                        crate::layout::Node::new_extended_glyph_vert(
+                            font,
+                            *ch,
+                            min_height,
+                            size,
+                            style.into(),
+                            *color,
+                            true,
+                        )
+                    });
+
+                    if let Some(translation) = translation {
+                        *self = Field::Layout {
+                            translation,
+                            italic_correction: 0.0,
+                        };
+                        return;
+                    }
+                }
+
                 let (color, glyph) = if let Some(glyph) = glyph {
                     (*color, glyph)
                 } else {
@@ -406,9 +608,21 @@ impl<Glyph: common::Glyph> Field<Glyph> {
 
                 let italic_correction = glyph.italic_correction();
                 let translation = crate::layout::Node::Glyph { glyph, color };
-                *self = Field::Layout {
-                    translation,
-                    italic_correction,
+
+                *self = if want_italic_correction && italic_correction > 0.0 {
+                    let children = vec![
+                        (0.0, translation),
+                        (0.0, crate::layout::Node::Glue(italic_correction)),
+                    ];
+                    Field::Layout {
+                        translation: crate::layout::Node::new_hbox(children),
+                        italic_correction: 0.0,
+                    }
+                } else {
+                    Field::Layout {
+                        translation,
+                        italic_correction,
+                    }
                 };
             }
             Field::Fallback(color) => {
@@ -462,8 +676,8 @@ impl<Glyph: common::Glyph> Field<Glyph> {
                 let mut children = Vec::new();
                 children.reserve_exact(3);
 
-                num.translate(backend, size, style.to_numerator(), false, false, true);
-                denom.translate(backend, size, style.to_denominator(), false, false, true);
+                num.translate(backend, size, style.to_numerator(), None, false, true);
+                denom.translate(backend, size, style.to_denominator(), None, false, true);
 
                 let num = num.take_translation();
                 let denom = denom.take_translation();
@@ -523,10 +737,125 @@ impl<Glyph: common::Glyph> Field<Glyph> {
                     italic_correction: 0.0,
                 };
             }
+            Field::Radical(radicand) => {
+                radicand.translate(backend, size, style.to_cramped(), None, false, true);
+                let (radicand, italic_correction) = radicand
+                    .take_translation()
+                    .unwrap_or((crate::layout::Node::Glue(0.0), 0.0));
+
+                let font = backend.get_font(Family::Italic);
+                let params = font.calculate_radical_params(size, style.into(), style.is_cramped());
+
+                // Like rule 11 of the TeXbook: the root sign covers the
+                // radicand, the gap and the bar. Half of any excess height of
+                // the sign goes to the gap. The tops of the sign and the bar
+                // are aligned
+                let content_height = radicand.height(false) + radicand.depth();
+                let min_height = content_height + params.vertical_gap + params.rule_thickness;
+                let sign = Delimiter {
+                    ch: '√',
+                    color: Color::Normal,
+                };
+                let sign = sign.translate_min_height(font, min_height, size, style, false);
+                let excess = (sign.height(false) + sign.depth() - min_height).max(0.0);
+                let gap = params.vertical_gap + excess / 2.0;
+                let top = radicand.height(false) + gap + params.rule_thickness;
+                let vshift = top - sign.height(false);
+
+                let width = radicand.advance(false) + italic_correction;
+                let vbox = crate::layout::Node::new_vbox(vec![
+                    (0.0, radicand),
+                    (0.0, crate::layout::Node::Glue(gap)),
+                    (
+                        0.0,
+                        crate::layout::Node::new_rule(width, params.rule_thickness),
+                    ),
+                    (0.0, crate::layout::Node::Glue(params.extra_ascender)),
+                ]);
+                let translation = crate::layout::Node::new_hbox(vec![(vshift, sign), (0.0, vbox)]);
+                *self = Field::Layout {
+                    translation,
+                    italic_correction: 0.0,
+                };
+            }
+            Field::Table(rows) => {
+                // Like the cells of TeX arrays, cells are at most in text style
+                let cell_style = style.min(Style::Text);
+                let rows: Vec<Vec<_>> = rows
+                    .iter_mut()
+                    .map(|row| {
+                        row.iter_mut()
+                            .map(|cell| {
+                                cell.translate(backend, size, cell_style, None, false, true);
+                                match cell.take_translation() {
+                                    Some((cell, italic_correction)) => {
+                                        (cell.advance(false) + italic_correction, cell)
+                                    }
+                                    None => (0.0, crate::layout::Node::Glue(0.0)),
+                                }
+                            })
+                            .collect()
+                    })
+                    .collect();
+
+                let mut widths = Vec::new();
+                for row in &rows {
+                    widths.resize(widths.len().max(row.len()), 0.0f32);
+                    for (width, (cell_width, _)) in widths.iter_mut().zip(row) {
+                        *width = width.max(*cell_width);
+                    }
+                }
+
+                // Columns are half a quad apart. The boxes of two rows are
+                // twice the axis height apart
+                let font = backend.get_font(Family::Italic);
+                let general_params =
+                    font.calculate_general_params(size, style.into(), style.is_cramped());
+                let column_gap = size / 2.0;
+                let row_gap = 2.0 * general_params.axis_height;
+
+                // Cells are centered in their column. The rows of the vbox go
+                // from bottom to top
+                let mut children = Vec::new();
+                for row in rows.into_iter().rev() {
+                    if !children.is_empty() {
+                        children.push((0.0, crate::layout::Node::Glue(row_gap)));
+                    }
+
+                    // The margins of two neighbouring cells and the column
+                    // gap form a single glue between them
+                    let mut cells = Vec::new();
+                    let mut space = 0.0;
+                    for ((cell_width, cell), width) in row.into_iter().zip(&widths) {
+                        let margin = (width - cell_width) / 2.0;
+                        cells.push((0.0, crate::layout::Node::Glue(space + margin)));
+                        cells.push((0.0, cell));
+                        space = margin + column_gap;
+                    }
+                    cells.push((0.0, crate::layout::Node::Glue(space - column_gap)));
+                    children.push((0.0, crate::layout::Node::new_hbox(cells)));
+                }
+
+                // The table is centered on the axis
+                let vbox = crate::layout::Node::new_vbox(children);
+                let vshift = general_params.axis_height - (vbox.height(false) - vbox.depth()) / 2.0;
+                let translation = crate::layout::Node::new_hbox(vec![(vshift, vbox)]);
+                *self = Field::Layout {
+                    translation,
+                    italic_correction: 0.0,
+                };
+            }
             Field::Empty | Field::Layout { .. } => {}
         }
     }
 
+    fn translation(&self) -> Option<&crate::layout::Node<Glyph>> {
+        match self {
+            Field::Layout { translation, .. } => Some(translation),
+            _ => None,
+        }
+    }
+
     fn take_translation(&mut self) -> Option<(crate::layout::Node<Glyph>, f32)> {
         let mut f = Field::Empty;
         std::mem::swap(&mut f, self);
@@ -1,5 +1,8 @@
 use crate::{
-    common::font_params::{FractionParams, ScriptParams},
+    common::{
+        font_params::{FractionParams, ScriptParams},
+        Corner,
+    },
     layout::Node,
 };
 
@@ -45,6 +48,51 @@ pub fn calculate_script_shifts<G: crate::common::Glyph>(
     (-vshift_down, vshift_up)
 }
 
+/// Horizontal kerning of the scripts using the math kerning of the nucleus
+/// and the scripts. Expects the vertical shifts returned by
+/// `calculate_script_shifts`. Returns the kerns of the subscript and the superscript
+pub fn calculate_script_kerns<G: crate::common::Glyph>(
+    nucleus: &Option<Node<G>>,
+    subscript: &Option<Node<G>>,
+    superscript: &Option<Node<G>>,
+    subscript_vshift: f32,
+    superscript_vshift: f32,
+) -> (f32, f32) {
+    let base = match nucleus.as_ref().and_then(|n| n.last_glyph()) {
+        Some(base) => base,
+        None => return (0.0, 0.0),
+    };
+
+    // The kerning is evaluated at two heights. To avoid collisions, the larger
+    // one is used. Heights are relative to the baseline of the nucleus
+    let kern = |script: &G, base_corner, script_corner, shift: f32, heights: [f32; 2]| {
+        heights
+            .iter()
+            .map(|&h| base.math_kern(base_corner, h) + script.math_kern(script_corner, h - shift))
+            .fold(f32::NEG_INFINITY, f32::max)
+    };
+
+    let subscript_kern = subscript
+        .as_ref()
+        .and_then(|s| Some((s.first_glyph()?, s.height(false))))
+        .map_or(0.0, |(script, script_height)| {
+            let heights = [script_height + subscript_vshift, -base.depth()];
+            let corners = (Corner::BottomRight, Corner::TopLeft);
+            kern(script, corners.0, corners.1, subscript_vshift, heights)
+        });
+
+    let superscript_kern = superscript
+        .as_ref()
+        .and_then(|s| Some((s.first_glyph()?, s.depth())))
+        .map_or(0.0, |(script, script_depth)| {
+            let heights = [superscript_vshift - script_depth, base.height()];
+            let corners = (Corner::TopRight, Corner::BottomLeft);
+            kern(script, corners.0, corners.1, superscript_vshift, heights)
+        });
+
+    (subscript_kern, superscript_kern)
+}
+
 pub fn calculate_numerator_gap<G: crate::common::Glyph>(
     axis_height: f32,
     params: &FractionParams,
@@ -64,3 +112,78 @@ pub fn calculate_denominator_gap<G: crate::common::Glyph>(
     let gap_after_shift = dp.shift - denom.height(true) + axis_height - params.rule_thickness / 2.0;
     gap_after_shift.max(dp.gap_min)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{common::Corner, layout::Node};
+
+    /// A glyph whose top right corner is cut in above a height of 5
+    #[derive(Clone)]
+    struct TestGlyph {
+        height: f32,
+        depth: f32,
+    }
+
+    impl crate::common::Glyph for TestGlyph {
+        fn height(&self) -> f32 {
+            self.height
+        }
+
+        fn depth(&self) -> f32 {
+            self.depth
+        }
+
+        fn advance(&self) -> f32 {
+            10.0
+        }
+
+        fn italic_correction(&self) -> f32 {
+            0.0
+        }
+
+        fn ink_extent(&self) -> (f32, f32) {
+            (0.0, 10.0)
+        }
+
+        fn math_kern(&self, corner: Corner, height: f32) -> f32 {
+            match corner {
+                Corner::TopRight if height > 5.0 => -3.0,
+                Corner::TopRight => -1.0,
+                _ => 0.0,
+            }
+        }
+
+        fn set_advance(&mut self, _advance: f32) {}
+    }
+
+    fn glyph(height: f32, depth: f32) -> Option<Node<TestGlyph>> {
+        let glyph = TestGlyph { height, depth };
+        Some(Node::Glyph {
+            glyph,
+            color: crate::common::Color::Normal,
+        })
+    }
+
+    #[test]
+    fn test_script_kerns() {
+        let kerns = |superscript_vshift| {
+            super::calculate_script_kerns(
+                &glyph(10.0, 0.0),
+                &glyph(4.0, 0.0),
+                &glyph(4.0, 1.0),
+                -2.0,
+                superscript_vshift,
+            )
+        };
+
+        // Both heights are within the cut-in
+        assert_eq!(kerns(8.0), (0.0, -3.0));
+
+        // The bottom of the superscript is below the cut-in
+        assert_eq!(kerns(4.0), (0.0, -1.0));
+
+        // Without a nucleus, there is no kerning
+        let kerns = super::calculate_script_kerns(&None, &None, &glyph(4.0, 1.0), 0.0, 8.0);
+        assert_eq!(kerns, (0.0, 0.0));
+    }
+}
@@ -0,0 +1,276 @@
+use super::{Atom, AtomType, Delimiter, Field, MathList, Node};
+use crate::common::{self, Color, Family};
+use crate::parser::tables::CharClassification;
+use core::fmt::{Result, Write};
+
+/// Writes the formula as a MathML `<math>` element. The source is kept as
+/// annotation, given as encoding (e.g. `application/x-tex`) and source
+pub fn write<G: common::Glyph>(
+    list: &MathList<G>,
+    annotation: Option<(&str, &str)>,
+    out: &mut impl Write,
+) -> Result {
+    out.write_str(r#"<math xmlns="http://www.w3.org/1998/Math/MathML" display="block">"#)?;
+    if annotation.is_some() {
+        out.write_str("<semantics>")?;
+    }
+
+    out.write_str("<mrow>")?;
+    write_list(list, out)?;
+    out.write_str("</mrow>")?;
+
+    if let Some((encoding, src)) = annotation {
+        out.write_str(r#"<annotation encoding=""#)?;
+        write_escaped(encoding, out)?;
+        out.write_str(r#"">"#)?;
+        write_escaped(src, out)?;
+        out.write_str("</annotation></semantics>")?;
+    }
+    out.write_str("</math>")
+}
+
+/// Consecutive symbols which are written as a single token element
+#[derive(Clone, Copy, PartialEq)]
+enum Run {
+    /// Digits and decimal points, e.g. `<mn>3.14</mn>`
+    Number,
+    /// Upright letters, e.g. `<mi>sin</mi>`
+    Word,
+    /// Source which could not be parsed, e.g. an unknown command
+    Error,
+}
+
+fn run_kind<G: common::Glyph>(atom: &Atom<G>) -> Option<Run> {
+    let is_ord = matches!(atom.atom_type, AtomType::Ord);
+    match atom.nucleus {
+        Field::Symbol(Color::Error, _, _) => Some(Run::Error),
+        Field::Symbol(_, _, ch) if is_ord && (ch.is_ascii_digit() || ch == '.') => {
+            Some(Run::Number)
+        }
+        Field::Symbol(_, Family::Roman, ch) if is_ord && ch.is_ascii_alphabetic() => {
+            Some(Run::Word)
+        }
+        _ => None,
+    }
+}
+
+fn has_scripts<G: common::Glyph>(atom: &Atom<G>) -> bool {
+    !atom.subscript.is_empty() || !atom.superscript.is_empty()
+}
+
+/// Whether the list is written as one element, e.g. a single word
+fn is_single_element<G: common::Glyph>(list: &MathList<G>) -> bool {
+    let Some((Node::Atom(last), init)) = list.0.split_last() else {
+        return false;
+    };
+    let kind = run_kind(last);
+    init.is_empty()
+        || kind.is_some()
+            && init
+                .iter()
+                .all(|Node::Atom(atom)| run_kind(atom) == kind && !has_scripts(atom))
+}
+
+fn write_list<G: common::Glyph>(list: &MathList<G>, out: &mut impl Write) -> Result {
+    let atoms: Vec<_> = list.0.iter().map(|Node::Atom(atom)| atom).collect();
+
+    let mut idx = 0;
+    while idx < atoms.len() {
+        let first = atoms[idx];
+        // A number starts with a digit, a lone `.` is punctuation
+        let kind = run_kind(first).filter(|kind| {
+            *kind != Run::Number || matches!(first.nucleus, Field::Symbol(_, _, ch) if ch != '.')
+        });
+        let Some(kind) = kind else {
+            write_atom(first, out)?;
+            idx += 1;
+            continue;
+        };
+
+        // The scripts of the last symbol belong to the whole run
+        let mut end = idx;
+        while end + 1 < atoms.len()
+            && !has_scripts(atoms[end])
+            && run_kind(atoms[end + 1]) == Some(kind)
+        {
+            end += 1;
+        }
+        let text: String = atoms[idx..=end]
+            .iter()
+            .filter_map(|atom| match atom.nucleus {
+                Field::Symbol(_, _, ch) => Some(ch),
+                _ => None,
+            })
+            .collect();
+
+        let last = atoms[end];
+        write_scripts(last, out, |out| match kind {
+            Run::Number => write_token("mn", "", &text, out),
+            Run::Word if text.chars().count() == 1 => {
+                write_token("mi", r#" mathvariant="normal""#, &text, out)
+            }
+            Run::Word => write_token("mi", "", &text, out),
+            Run::Error => write_error(&text, out),
+        })?;
+        idx = end + 1;
+    }
+
+    Ok(())
+}
+
+fn write_atom<G: common::Glyph>(atom: &Atom<G>, out: &mut impl Write) -> Result {
+    write_scripts(atom, out, |out| match atom.nucleus {
+        Field::Symbol(color, family, ch) => write_symbol(&atom.atom_type, color, family, ch, out),
+        ref field => write_element(field, out),
+    })
+}
+
+/// Writes the nucleus with the scripts of the atom. Scripts of operators
+/// become limits, like in display style
+fn write_scripts<G: common::Glyph, W: Write>(
+    atom: &Atom<G>,
+    out: &mut W,
+    nucleus: impl FnOnce(&mut W) -> Result,
+) -> Result {
+    let limits = matches!(atom.atom_type, AtomType::Op);
+    let tag = match (atom.subscript.is_empty(), atom.superscript.is_empty()) {
+        (true, true) => return nucleus(out),
+        (false, true) if limits => "munder",
+        (true, false) if limits => "mover",
+        (false, false) if limits => "munderover",
+        (false, true) => "msub",
+        (true, false) => "msup",
+        (false, false) => "msubsup",
+    };
+
+    write!(out, "<{}>", tag)?;
+    nucleus(out)?;
+    if !atom.subscript.is_empty() {
+        write_element(&atom.subscript, out)?;
+    }
+    if !atom.superscript.is_empty() {
+        write_element(&atom.superscript, out)?;
+    }
+    write!(out, "</{}>", tag)
+}
+
+/// Writes a field as a single element, as required for the children of e.g.
+/// `<mfrac>`
+fn write_element<G: common::Glyph>(field: &Field<G>, out: &mut impl Write) -> Result {
+    match field {
+        Field::Empty | Field::Layout { .. } => out.write_str("<mrow></mrow>"),
+        Field::Symbol(color, family, ch) => {
+            let atom_type = CharClassification::classify(*ch)
+                .to_atom_type()
+                .unwrap_or(AtomType::Ord);
+            write_symbol(&atom_type, *color, *family, *ch, out)
+        }
+        Field::Fallback(_) => write_error("?", out),
+        Field::MathList(left, list, right) => {
+            if left.is_none() && right.is_none() && is_single_element(list) {
+                return write_list(list, out);
+            }
+
+            out.write_str("<mrow>")?;
+            if let Some(left) = left {
+                write_fence(left, "prefix", out)?;
+            }
+            write_list(list, out)?;
+            if let Some(right) = right {
+                write_fence(right, "postfix", out)?;
+            }
+            out.write_str("</mrow>")
+        }
+        Field::Fraction(numerator, denominator) => {
+            out.write_str("<mfrac>")?;
+            write_element(numerator, out)?;
+            write_element(denominator, out)?;
+            out.write_str("</mfrac>")
+        }
+        Field::Radical(radicand) => {
+            out.write_str("<msqrt>")?;
+            write_element(radicand, out)?;
+            out.write_str("</msqrt>")
+        }
+        Field::Table(rows) => {
+            out.write_str("<mtable>")?;
+            for row in rows {
+                out.write_str("<mtr>")?;
+                for cell in row {
+                    out.write_str("<mtd>")?;
+                    write_element(cell, out)?;
+                    out.write_str("</mtd>")?;
+                }
+                out.write_str("</mtr>")?;
+            }
+            out.write_str("</mtable>")
+        }
+    }
+}
+
+fn write_fence(delimiter: &Delimiter, form: &str, out: &mut impl Write) -> Result {
+    let attributes = format!(r#" fence="true" form="{}""#, form);
+    write_token("mo", &attributes, &delimiter.ch.to_string(), out)
+}
+
+fn write_symbol(
+    atom_type: &AtomType,
+    color: Color,
+    family: Family,
+    ch: char,
+    out: &mut impl Write,
+) -> Result {
+    let text = ch.to_string();
+    if color == Color::Error {
+        return write_error(&text, out);
+    }
+
+    let is_operator = match atom_type {
+        AtomType::Ord | AtomType::Inner | AtomType::Acc => matches!(
+            CharClassification::classify(ch),
+            CharClassification::Fence | CharClassification::Punct
+        ),
+        _ => !ch.is_alphanumeric(),
+    };
+
+    if is_operator {
+        write_token("mo", "", &text, out)
+    } else if ch.is_ascii_digit() {
+        write_token("mn", "", &text, out)
+    } else if family == Family::Roman && (ch.is_ascii_alphabetic() || is_greek(ch)) {
+        // Single letters are italic by default
+        write_token("mi", r#" mathvariant="normal""#, &text, out)
+    } else {
+        let ch = family.math_alphanumeric(ch);
+        write_token("mi", "", &ch.to_string(), out)
+    }
+}
+
+fn is_greek(ch: char) -> bool {
+    matches!(ch, 'Α'..='Ω' | 'α'..='ω')
+}
+
+fn write_error(text: &str, out: &mut impl Write) -> Result {
+    out.write_str("<merror><mtext>")?;
+    write_escaped(text, out)?;
+    out.write_str("</mtext></merror>")
+}
+
+fn write_token(tag: &str, attributes: &str, text: &str, out: &mut impl Write) -> Result {
+    write!(out, "<{}{}>", tag, attributes)?;
+    write_escaped(text, out)?;
+    write!(out, "</{}>", tag)
+}
+
+fn write_escaped(text: &str, out: &mut impl Write) -> Result {
+    for ch in text.chars() {
+        match ch {
+            '&' => out.write_str("&amp;")?,
+            '<' => out.write_str("&lt;")?,
+            '>' => out.write_str("&gt;")?,
+            '"' => out.write_str("&quot;")?,
+            _ => out.write_char(ch)?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use super::{Delimiter, Field, MathList, Node};
+use crate::common;
+
+/// Describes a formula in spoken English, e.g. `x squared plus 1` for
+/// `x^2+1`. Used as the description of accessible images
+pub fn describe<G: common::Glyph>(list: &MathList<G>) -> String {
+    let mut words = Vec::new();
+    describe_list(list, &mut words);
+    words.join(" ")
+}
+
+fn describe_list<G: common::Glyph>(list: &MathList<G>, words: &mut Vec<String>) {
+    let mut previous_digit = false;
+
+    for node in &list.0 {
+        let Node::Atom(atom) = node;
+
+        // Consecutive digits are read as one number
+        let digit = match atom.nucleus {
+            Field::Symbol(_, _, ch) if ch.is_ascii_digit() || ch == '.' => Some(ch),
+            _ => None,
+        };
+        match (digit, words.last_mut()) {
+            (Some(ch), Some(last)) if previous_digit => last.push(ch),
+            _ => describe_field(&atom.nucleus, words),
+        }
+        let has_scripts = !atom.subscript.is_empty() || !atom.superscript.is_empty();
+        previous_digit = digit.is_some() && !has_scripts;
+
+        if !atom.subscript.is_empty() {
+            words.push("sub".to_owned());
+            describe_nested(&atom.subscript, "end sub", words);
+        }
+        if !atom.superscript.is_empty() {
+            describe_superscript(&atom.superscript, words);
+        }
+    }
+}
+
+fn describe_superscript<G: common::Glyph>(field: &Field<G>, words: &mut Vec<String>) {
+    match field {
+        Field::Symbol(_, _, '2') => words.push("squared".to_owned()),
+        Field::Symbol(_, _, '3') => words.push("cubed".to_owned()),
+        Field::Symbol(_, _, '′') => words.push("prime".to_owned()),
+        _ => {
+            words.push("to the power of".to_owned());
+            describe_nested(field, "end power", words);
+        }
+    }
+}
+
+/// Describes a field, followed by `end` if it consists of several words
+fn describe_nested<G: common::Glyph>(field: &Field<G>, end: &str, words: &mut Vec<String>) {
+    let start = words.len();
+    describe_field(field, words);
+    if words.len() > start + 1 {
+        words.push(end.to_owned());
+    }
+}
+
+fn describe_field<G: common::Glyph>(field: &Field<G>, words: &mut Vec<String>) {
+    match field {
+        Field::Empty | Field::Layout { .. } => {}
+        Field::Symbol(_, _, ch) => words.push(char_name(*ch)),
+        Field::Fallback(_) => words.push("unknown".to_owned()),
+        Field::MathList(left, list, right) => {
+            let delimiter = |delim: &Option<Delimiter>, words: &mut Vec<String>| {
+                if let Some(delim) = delim {
+                    words.push(char_name(delim.ch));
+                }
+            };
+            delimiter(left, words);
+            describe_list(list, words);
+            delimiter(right, words);
+        }
+        Field::Fraction(numerator, denominator) => {
+            let mut num = Vec::new();
+            let mut denom = Vec::new();
+            describe_field(numerator, &mut num);
+            describe_field(denominator, &mut denom);
+
+            let simple = num.len() <= 1 && denom.len() <= 1;
+            if !simple {
+                words.push("fraction".to_owned());
+            }
+            words.extend(num);
+            words.push("over".to_owned());
+            words.extend(denom);
+            if !simple {
+                words.push("end fraction".to_owned());
+            }
+        }
+        Field::Radical(radicand) => {
+            let start = words.len();
+            words.push("square root of".to_owned());
+            describe_field(radicand, words);
+            if words.len() > start + 2 {
+                words.push("end root".to_owned());
+            }
+        }
+        Field::Table(rows) => {
+            words.push("table".to_owned());
+            for row in rows {
+                words.push("row".to_owned());
+                for (idx, cell) in row.iter().enumerate() {
+                    if idx > 0 {
+                        words.push("and".to_owned());
+                    }
+                    describe_field(cell, words);
+                }
+            }
+            words.push("end table".to_owned());
+        }
+    }
+}
+
+/// The spoken name of a symbol. Symbols without an entry in the table use
+/// the name of their command, e.g. `alpha` for `\mitalpha`
+fn char_name(ch: char) -> String {
+    let name = match ch {
+        '+' => "plus",
+        '−' | '-' => "minus",
+        '±' => "plus or minus",
+        '∓' => "minus or plus",
+        '×' | '⋅' | '·' | '∗' => "times",
+        '÷' | '/' => "divided by",
+        '=' => "equals",
+        '≠' => "is not equal to",
+        '≈' => "is approximately equal to",
+        '≡' => "is equivalent to",
+        '<' => "is less than",
+        '>' => "is greater than",
+        '≤' => "is less than or equal to",
+        '≥' => "is greater than or equal to",
+        '∈' => "is an element of",
+        '→' => "goes to",
+        '∞' => "infinity",
+        '∑' => "sum",
+        '∏' => "product",
+        '∫' => "integral",
+        '∂' => "partial",
+        '√' => "square root",
+        '(' => "open paren",
+        ')' => "close paren",
+        '[' => "open bracket",
+        ']' => "close bracket",
+        '{' => "open brace",
+        '}' => "close brace",
+        '|' => "vertical bar",
+        ',' => "comma",
+        '!' => "factorial",
+        '′' => "prime",
+        _ => return command_name(ch).unwrap_or_else(|| ch.to_string()),
+    };
+
+    name.to_owned()
+}
+
+fn command_name(ch: char) -> Option<String> {
+    if ch.is_alphanumeric() && ch.is_ascii() {
+        return None;
+    }
+
+    let (command, _) = crate::parser::char_commands().find(|&(_, c)| c == ch)?;
+    let Some(letter) = ["mup", "mit"]
+        .iter()
+        .find_map(|prefix| command.strip_prefix(prefix))
+        .filter(|name| !name.is_empty())
+    else {
+        return Some(command.to_owned());
+    };
+
+    // Upright and italic letters, e.g. `\mupGamma`
+    let mut chars = letter.chars();
+    match chars.next() {
+        Some(first) if first.is_uppercase() => Some(format!(
+            "capital {}{}",
+            first.to_lowercase(),
+            chars.as_str()
+        )),
+        _ => Some(letter.to_owned()),
+    }
+}
@@ -0,0 +1,188 @@
+use crate::common::Color;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Rgba {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+impl Rgba {
+    pub const BLACK: Self = Self::new(0, 0, 0, 255);
+    pub const WHITE: Self = Self::new(255, 255, 255, 255);
+    pub const RED: Self = Self::new(255, 0, 0, 255);
+
+    pub const fn new(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+}
+
+/// Padding around the formula in pt
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Padding {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl Padding {
+    pub const fn uniform(padding: f32) -> Self {
+        Self::symmetric(padding, padding)
+    }
+
+    pub const fn symmetric(horizontal: f32, vertical: f32) -> Self {
+        Self {
+            left: horizontal,
+            right: horizontal,
+            top: vertical,
+            bottom: vertical,
+        }
+    }
+}
+
+impl Default for Padding {
+    fn default() -> Self {
+        Self::symmetric(10.0, 5.0)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RenderOptions {
+    /// Font size in pt
+    pub font_size: f32,
+    pub padding: Padding,
+    /// Resolution of raster images. 72 dpi means one pixel per pt
+    pub dpi: f32,
+    pub foreground: Rgba,
+    pub error_color: Rgba,
+    /// `None` means transparent
+    pub background: Option<Rgba>,
+    /// Use the bounding box of the ink instead of the typographic box
+    /// (advance, height and depth) to determine the image size
+    pub crop_to_ink: bool,
+    /// Number of decimals of the coordinates in svg output. `None` writes
+    /// them with full precision
+    pub svg_precision: Option<usize>,
+    /// Adds a title, a spoken description and groups with the atom types to
+    /// svg output
+    pub svg_accessibility: bool,
+    /// Writes the glyphs of svg output as text, using the embedded font, so
+    /// that the formula can be selected and searched. Glyphs without a
+    /// character of their own, e.g. enlarged delimiters, stay outlines
+    pub svg_text: bool,
+    /// Prefix of the ids in svg output. By default, the ids are derived from
+    /// the image, so that different formulas in one html document do not
+    /// collide. Set it when the same formula is embedded more than once
+    pub svg_id_prefix: Option<String>,
+    /// The syntax of the source, e.g. LaTeX or AsciiMath
+    pub syntax: crate::parser::Syntax,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            font_size: 36.0,
+            padding: Padding::default(),
+            dpi: 96.0,
+            foreground: Rgba::BLACK,
+            error_color: Rgba::RED,
+            background: None,
+            crop_to_ink: false,
+            svg_precision: Some(2),
+            svg_accessibility: false,
+            svg_text: false,
+            svg_id_prefix: None,
+            syntax: crate::parser::Syntax::Latex,
+        }
+    }
+}
+
+impl RenderOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    pub fn with_padding(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn with_dpi(mut self, dpi: f32) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    /// Sets the dpi such that one pt equals `scale` pixels
+    pub fn with_scale(self, scale: f32) -> Self {
+        self.with_dpi(scale * 72.0)
+    }
+
+    pub fn with_foreground(mut self, color: Rgba) -> Self {
+        self.foreground = color;
+        self
+    }
+
+    pub fn with_error_color(mut self, color: Rgba) -> Self {
+        self.error_color = color;
+        self
+    }
+
+    pub fn with_background(mut self, color: Option<Rgba>) -> Self {
+        self.background = color;
+        self
+    }
+
+    pub fn with_crop_to_ink(mut self, crop_to_ink: bool) -> Self {
+        self.crop_to_ink = crop_to_ink;
+        self
+    }
+
+    pub fn with_svg_precision(mut self, precision: Option<usize>) -> Self {
+        self.svg_precision = precision;
+        self
+    }
+
+    pub fn with_svg_accessibility(mut self, accessibility: bool) -> Self {
+        self.svg_accessibility = accessibility;
+        self
+    }
+
+    pub fn with_svg_text(mut self, text: bool) -> Self {
+        self.svg_text = text;
+        self
+    }
+
+    pub fn with_svg_id_prefix(mut self, prefix: Option<String>) -> Self {
+        self.svg_id_prefix = prefix;
+        self
+    }
+
+    pub fn with_syntax(mut self, syntax: crate::parser::Syntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+
+    /// Pixels per pt
+    pub fn scale(&self) -> f32 {
+        self.dpi / 72.0
+    }
+
+    pub fn color(&self, color: Color) -> Rgba {
+        match color {
+            Color::Normal => self.foreground,
+            Color::Error => self.error_color,
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    common::Color,
+    common::{Color, Family},
     mathlist::{Atom, AtomType, Delimiter, Field, MathList},
 };
 use nom::{
@@ -11,8 +11,104 @@ use std::marker::PhantomData;
 
 use self::error::{make_recoverable_error, ErrorKind};
 
+pub mod asciimath;
 mod error;
-mod tables;
+pub mod mathml;
+pub(crate) mod tables;
+pub mod typst;
+
+/// The syntax of the source of a formula
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Syntax {
+    #[default]
+    Latex,
+    AsciiMath,
+    /// Presentation MathML
+    MathMl,
+    /// The math mode of Typst
+    Typst,
+}
+
+impl Syntax {
+    /// The encoding of the source in a MathML annotation, `None` if the
+    /// source is MathML itself
+    pub fn annotation_encoding(self) -> Option<&'static str> {
+        match self {
+            Syntax::Latex => Some("application/x-tex"),
+            Syntax::AsciiMath => Some("text/x-asciimath"),
+            Syntax::MathMl => None,
+            Syntax::Typst => Some("text/x-typst"),
+        }
+    }
+
+    /// The name of the syntax in image metadata, e.g. `asciimath`
+    pub fn name(self) -> &'static str {
+        match self {
+            Syntax::Latex => "latex",
+            Syntax::AsciiMath => "asciimath",
+            Syntax::MathMl => "mathml",
+            Syntax::Typst => "typst",
+        }
+    }
+
+    /// The syntax with the given [`name`](Self::name)
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Syntax::Latex,
+            Syntax::AsciiMath,
+            Syntax::MathMl,
+            Syntax::Typst,
+        ]
+        .into_iter()
+        .find(|syntax| syntax.name() == name)
+    }
+}
+
+/// All commands which stand for a single character, e.g. `\infty`
+pub fn char_commands() -> impl Iterator<Item = (&'static str, char)> {
+    tables::char_commands()
+}
+
+/// The family a character is rendered in, or `None` if the parser does not
+/// accept it as a symbol
+pub fn symbol_family(ch: char) -> Option<Family> {
+    tables::CharClassification::classify(ch).to_atom_type()?;
+    Some(default_family(ch))
+}
+
+/// Greek letters by name, e.g. `alpha` and `Gamma`. Like in LaTeX, lowercase
+/// letters are italic (`\mitalpha`) and uppercase letters upright
+/// (`\mupGamma`)
+fn greek_letter(name: &str) -> Option<char> {
+    let prefix = match name.starts_with(char::is_uppercase) {
+        true => "mup",
+        false => "mit",
+    };
+    tables::command_to_char(&format!("{}{}", prefix, name))
+}
+
+/// Length of the decimal number at the start of the source, e.g. `12.5`.
+/// The decimal point needs a digit after it
+fn number_len(src: &str) -> usize {
+    let digits = |src: &str| {
+        src.find(|ch: char| !ch.is_ascii_digit())
+            .unwrap_or(src.len())
+    };
+    let len = digits(src);
+    match src[len..].strip_prefix('.').map(digits) {
+        Some(fraction) if fraction > 0 && len > 0 => len + 1 + fraction,
+        _ => len,
+    }
+}
+
+fn default_family(ch: char) -> Family {
+    // Like in TeX, latin letters are italic by default. The backend maps
+    // them to the math alphabets
+    match ch.is_ascii_alphabetic() {
+        true => Family::Italic,
+        false => Family::Roman,
+    }
+}
 
 type ParseResult<'a, T> = nom::IResult<&'a str, T, error::Error<&'a str>>;
 
@@ -37,14 +133,14 @@ impl<Glyph: crate::common::Glyph> ParserImp<Glyph> {
         let ch = match ch {
             '-' => '−',
             '*' => '∗',
-            'h' => 'ℎ',
-            'A'..='Z' => char::from_u32(ch as u32 + '𝐴' as u32 - 'A' as u32).unwrap(),
-            'a'..='z' => char::from_u32(ch as u32 + '𝑎' as u32 - 'a' as u32).unwrap(),
             _ => ch,
         };
 
         let (symbol, atom_type) = match tables::CharClassification::classify(ch).to_atom_type() {
-            Some(atom_type) => (Field::Symbol(Color::Normal, ch), atom_type),
+            Some(atom_type) => (
+                Field::Symbol(Color::Normal, default_family(ch), ch),
+                atom_type,
+            ),
             None => (Field::Fallback(Color::Error), AtomType::Ord),
         };
 
@@ -52,10 +148,10 @@ impl<Glyph: crate::common::Glyph> ParserImp<Glyph> {
     }
 
     fn make_error_field(text: &[&str]) -> Field<Glyph> {
-        let len = text.into_iter().map(|x| x.len()).sum();
+        let len = text.iter().map(|x| x.len()).sum();
         match len {
             0 => Field::Empty,
-            1 => Field::Symbol(Color::Error, text[0].chars().next().unwrap()),
+            1 => Field::Symbol(Color::Error, Family::Roman, text[0].chars().next().unwrap()),
             _ => {
                 let mut builder = crate::mathlist::Builder::default();
                 for text in text {
@@ -70,20 +166,41 @@ impl<Glyph: crate::common::Glyph> ParserImp<Glyph> {
 
     fn delimiter(src: &str) -> ParseResult<'_, Option<Delimiter>> {
         let (src, _) = Self::whitespace(src)?;
-        if src.starts_with(".") {
-            return Ok((&src[1..], None));
+        if let Some(src) = src.strip_prefix('.') {
+            return Ok((src, None));
         }
 
         let (remaining, (_, field)) = Self::field(src, false)?;
         let delim = match field {
             Field::Empty => None,
-            Field::Symbol(color, ch) => Some(Delimiter { ch, color }),
+            Field::Symbol(color, _, ch) => Some(Delimiter { ch, color }),
             _ => return make_recoverable_error(src, ErrorKind::InvalidDelimiter),
         };
 
         Ok((remaining, delim))
     }
 
+    /// The symbols of a text as one field, e.g. `sin` or `12`. Whitespace is
+    /// dropped
+    fn text_field(text: &str, family: Family) -> Field<Glyph> {
+        let mut builder = crate::mathlist::Builder::default();
+        let mut symbols = text
+            .chars()
+            .filter(|ch| !ch.is_whitespace())
+            .map(|ch| Field::Symbol(Color::Normal, family, ch));
+
+        match (symbols.next(), symbols.next()) {
+            (None, _) => Field::Empty,
+            (Some(symbol), None) => symbol,
+            (Some(first), Some(second)) => {
+                for symbol in [first, second].into_iter().chain(symbols) {
+                    builder.add_atom(Atom::new(AtomType::Ord, symbol));
+                }
+                Field::MathList(None, builder.finish(), None)
+            }
+        }
+    }
+
     fn handle_command<'a>(
         cmd: &'_ str,
         remaining: &'a str,
@@ -115,6 +232,23 @@ impl<Glyph: crate::common::Glyph> ParserImp<Glyph> {
                 let field = Field::Fraction(numerator.into(), denominator.into());
                 (remaining, (AtomType::Inner, field))
             }
+            "mathrm" | "mathit" | "mathbf" | "mathsf" | "mathtt" | "operatorname" => {
+                let family = match cmd {
+                    "mathit" => Family::Italic,
+                    "mathbf" => Family::Bold,
+                    "mathsf" => Family::SansSerif,
+                    "mathtt" => Family::Monospace,
+                    _ => Family::Roman,
+                };
+                let atom_type = match cmd {
+                    "operatorname" => AtomType::Op,
+                    _ => AtomType::Ord,
+                };
+
+                let (remaining, (_, mut field)) = Self::field(remaining, false)?;
+                field.set_family(family);
+                (remaining, (atom_type, field))
+            }
             "mathop" => {
                 let (remaining, (_, field)) = Self::field(remaining, false)?;
                 (remaining, (AtomType::Op, field))
@@ -252,6 +386,27 @@ impl<Glyph: crate::common::Glyph> ParserImp<Glyph> {
     }
 }
 
-pub fn parse<G: crate::common::Glyph>(src: &str) -> Option<MathList<G>> {
-    ParserImp::parse(src, None).ok().map(|r| r.1)
+pub fn parse<G: crate::common::Glyph>(src: &str) -> crate::Result<MathList<G>> {
+    match ParserImp::parse(src, None) {
+        Ok((_, list)) => Ok(list),
+        Err(nom::Err::Error(err) | nom::Err::Failure(err)) => Err(crate::Error::Parse {
+            position: err.position(src),
+        }),
+        Err(nom::Err::Incomplete(_)) => Err(crate::Error::Parse {
+            position: src.len(),
+        }),
+    }
+}
+
+/// Parses the source in the given syntax
+pub fn parse_with_syntax<G: crate::common::Glyph>(
+    src: &str,
+    syntax: Syntax,
+) -> crate::Result<MathList<G>> {
+    match syntax {
+        Syntax::Latex => parse(src),
+        Syntax::AsciiMath => asciimath::parse(src),
+        Syntax::MathMl => mathml::parse(src),
+        Syntax::Typst => typst::parse(src),
+    }
 }
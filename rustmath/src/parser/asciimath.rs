@@ -0,0 +1,495 @@
+//! Parser for AsciiMath, e.g. `sum_(i=1)^n i^2 = (n(n+1)(2n+1))/6`. Like in
+//! AsciiMath, the brackets around fractions, scripts and arguments only group
+//! and are not shown. Any source is accepted, unsupported names like `hat`
+//! are shown in the error color.
+
+use super::{greek_letter, number_len, ParserImp};
+use crate::common::{self, Color, Family};
+use crate::mathlist::{Atom, AtomType, Builder, Delimiter, Field, MathList};
+
+pub fn parse<G: common::Glyph>(src: &str) -> crate::Result<MathList<G>> {
+    let mut parser = Parser {
+        tokens: tokenize(src),
+        pos: 0,
+    };
+    Ok(parser.list(Stop::End))
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Token<'a> {
+    Symbol(char),
+    /// Function names like `sin`, in upright letters
+    Word(&'a str),
+    /// Functions with limits like `lim`
+    Limit(&'a str),
+    Number(&'a str),
+    Text(&'a str),
+    /// Brackets, `None` for the invisible `{:` and `:}`
+    Left(Option<char>),
+    Right(Option<char>),
+    /// Brackets which open and close, e.g. `|`
+    Bar(char),
+    Sqrt,
+    Font(Family),
+    /// Unary functions which put delimiters around their argument
+    Fenced(char, char),
+    Frac,
+    Unsupported(&'a str),
+    Sub,
+    Sup,
+    Slash,
+}
+
+const SYMBOLS: &[(&str, Token)] = &[
+    // Operators
+    ("+", Token::Symbol('+')),
+    ("-", Token::Symbol('−')),
+    ("*", Token::Symbol('⋅')),
+    ("**", Token::Symbol('∗')),
+    ("***", Token::Symbol('⋆')),
+    ("//", Token::Symbol('/')),
+    ("\\\\", Token::Symbol('\\')),
+    ("xx", Token::Symbol('×')),
+    ("-:", Token::Symbol('÷')),
+    ("@", Token::Symbol('∘')),
+    ("o+", Token::Symbol('⊕')),
+    ("ox", Token::Symbol('⊗')),
+    ("o.", Token::Symbol('⊙')),
+    ("+-", Token::Symbol('±')),
+    ("-+", Token::Symbol('∓')),
+    ("^^", Token::Symbol('∧')),
+    ("vv", Token::Symbol('∨')),
+    ("nn", Token::Symbol('∩')),
+    ("uu", Token::Symbol('∪')),
+    ("sum", Token::Symbol('∑')),
+    ("prod", Token::Symbol('∏')),
+    ("^^^", Token::Symbol('⋀')),
+    ("vvv", Token::Symbol('⋁')),
+    ("nnn", Token::Symbol('⋂')),
+    ("uuu", Token::Symbol('⋃')),
+    ("int", Token::Symbol('∫')),
+    ("oint", Token::Symbol('∮')),
+    // Relations
+    ("!=", Token::Symbol('≠')),
+    ("<=", Token::Symbol('≤')),
+    ("le", Token::Symbol('≤')),
+    (">=", Token::Symbol('≥')),
+    ("ge", Token::Symbol('≥')),
+    ("-<", Token::Symbol('≺')),
+    (">-", Token::Symbol('≻')),
+    ("in", Token::Symbol('∈')),
+    ("!in", Token::Symbol('∉')),
+    ("sub", Token::Symbol('⊂')),
+    ("sup", Token::Symbol('⊃')),
+    ("sube", Token::Symbol('⊆')),
+    ("supe", Token::Symbol('⊇')),
+    ("-=", Token::Symbol('≡')),
+    ("~=", Token::Symbol('≅')),
+    ("~~", Token::Symbol('≈')),
+    ("prop", Token::Symbol('∝')),
+    // Logic
+    ("not", Token::Symbol('¬')),
+    ("=>", Token::Symbol('⇒')),
+    ("<=>", Token::Symbol('⇔')),
+    ("AA", Token::Symbol('∀')),
+    ("EE", Token::Symbol('∃')),
+    ("_|_", Token::Symbol('⊥')),
+    ("TT", Token::Symbol('⊤')),
+    ("|--", Token::Symbol('⊢')),
+    ("|==", Token::Symbol('⊨')),
+    // Miscellaneous
+    ("del", Token::Symbol('∂')),
+    ("grad", Token::Symbol('∇')),
+    ("O/", Token::Symbol('∅')),
+    ("oo", Token::Symbol('∞')),
+    ("aleph", Token::Symbol('ℵ')),
+    ("...", Token::Symbol('…')),
+    ("cdots", Token::Symbol('⋯')),
+    ("vdots", Token::Symbol('⋮')),
+    ("ddots", Token::Symbol('⋱')),
+    ("/_", Token::Symbol('∠')),
+    (":.", Token::Symbol('∴')),
+    ("'", Token::Symbol('′')),
+    ("CC", Token::Symbol('ℂ')),
+    ("NN", Token::Symbol('ℕ')),
+    ("QQ", Token::Symbol('ℚ')),
+    ("RR", Token::Symbol('ℝ')),
+    ("ZZ", Token::Symbol('ℤ')),
+    // Arrows
+    ("uarr", Token::Symbol('↑')),
+    ("darr", Token::Symbol('↓')),
+    ("rarr", Token::Symbol('→')),
+    ("->", Token::Symbol('→')),
+    ("|->", Token::Symbol('↦')),
+    ("larr", Token::Symbol('←')),
+    ("harr", Token::Symbol('↔')),
+    ("rArr", Token::Symbol('⇒')),
+    ("lArr", Token::Symbol('⇐')),
+    ("hArr", Token::Symbol('⇔')),
+    // Functions
+    ("sin", Token::Word("sin")),
+    ("cos", Token::Word("cos")),
+    ("tan", Token::Word("tan")),
+    ("sec", Token::Word("sec")),
+    ("csc", Token::Word("csc")),
+    ("cot", Token::Word("cot")),
+    ("arcsin", Token::Word("arcsin")),
+    ("arccos", Token::Word("arccos")),
+    ("arctan", Token::Word("arctan")),
+    ("sinh", Token::Word("sinh")),
+    ("cosh", Token::Word("cosh")),
+    ("tanh", Token::Word("tanh")),
+    ("exp", Token::Word("exp")),
+    ("log", Token::Word("log")),
+    ("ln", Token::Word("ln")),
+    ("dim", Token::Word("dim")),
+    ("mod", Token::Word("mod")),
+    ("gcd", Token::Word("gcd")),
+    ("lcm", Token::Word("lcm")),
+    ("det", Token::Word("det")),
+    ("lim", Token::Limit("lim")),
+    ("Lim", Token::Limit("Lim")),
+    ("max", Token::Limit("max")),
+    ("min", Token::Limit("min")),
+    // Brackets
+    ("(", Token::Left(Some('('))),
+    (")", Token::Right(Some(')'))),
+    ("[", Token::Left(Some('['))),
+    ("]", Token::Right(Some(']'))),
+    ("{", Token::Left(Some('{'))),
+    ("}", Token::Right(Some('}'))),
+    ("(:", Token::Left(Some('⟨'))),
+    (":)", Token::Right(Some('⟩'))),
+    ("<<", Token::Left(Some('⟨'))),
+    (">>", Token::Right(Some('⟩'))),
+    ("{:", Token::Left(None)),
+    (":}", Token::Right(None)),
+    ("|__", Token::Left(Some('⌊'))),
+    ("__|", Token::Right(Some('⌋'))),
+    ("|~", Token::Left(Some('⌈'))),
+    ("~|", Token::Right(Some('⌉'))),
+    ("|", Token::Bar('|')),
+    ("||", Token::Bar('‖')),
+    // Unary and binary functions
+    ("sqrt", Token::Sqrt),
+    ("bb", Token::Font(Family::Bold)),
+    ("sf", Token::Font(Family::SansSerif)),
+    ("tt", Token::Font(Family::Monospace)),
+    ("rm", Token::Font(Family::Roman)),
+    ("abs", Token::Fenced('|', '|')),
+    ("norm", Token::Fenced('‖', '‖')),
+    ("floor", Token::Fenced('⌊', '⌋')),
+    ("ceil", Token::Fenced('⌈', '⌉')),
+    ("frac", Token::Frac),
+    ("root", Token::Unsupported("root")),
+    ("stackrel", Token::Unsupported("stackrel")),
+    ("overset", Token::Unsupported("overset")),
+    ("underset", Token::Unsupported("underset")),
+    ("hat", Token::Unsupported("hat")),
+    ("bar", Token::Unsupported("bar")),
+    ("ul", Token::Unsupported("ul")),
+    ("vec", Token::Unsupported("vec")),
+    ("dot", Token::Unsupported("dot")),
+    ("ddot", Token::Unsupported("ddot")),
+    ("tilde", Token::Unsupported("tilde")),
+    ("bbb", Token::Unsupported("bbb")),
+    ("cc", Token::Unsupported("cc")),
+    ("fr", Token::Unsupported("fr")),
+    // Scripts
+    ("_", Token::Sub),
+    ("^", Token::Sup),
+    ("/", Token::Slash),
+];
+
+/// Names of Greek letters, which are matched as prefix like the symbols
+const GREEK: &[&str] = &[
+    "alpha",
+    "beta",
+    "gamma",
+    "Gamma",
+    "delta",
+    "Delta",
+    "epsilon",
+    "varepsilon",
+    "zeta",
+    "eta",
+    "theta",
+    "Theta",
+    "vartheta",
+    "iota",
+    "kappa",
+    "lambda",
+    "Lambda",
+    "mu",
+    "nu",
+    "xi",
+    "Xi",
+    "pi",
+    "Pi",
+    "rho",
+    "sigma",
+    "Sigma",
+    "tau",
+    "upsilon",
+    "Upsilon",
+    "phi",
+    "Phi",
+    "varphi",
+    "chi",
+    "psi",
+    "Psi",
+    "omega",
+    "Omega",
+];
+
+fn tokenize(src: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut src = src.trim_start();
+
+    while let Some(ch) = src.chars().next() {
+        let (token, len) = if let Some(text) = src.strip_prefix('"') {
+            let len = text.find('"').unwrap_or(text.len());
+            (Token::Text(&text[..len]), (len + 2).min(src.len()))
+        } else if ch.is_ascii_digit() {
+            let len = number_len(src);
+            (Token::Number(&src[..len]), len)
+        } else if let Some((text, len)) = text_argument(src) {
+            (Token::Text(text), len)
+        } else {
+            // The longest name wins, e.g. `<=>` instead of `<=`
+            let symbol = SYMBOLS
+                .iter()
+                .filter(|(name, _)| src.starts_with(name))
+                .max_by_key(|(name, _)| name.len())
+                .map(|(name, token)| (*token, name.len()));
+            let greek = GREEK
+                .iter()
+                .filter(|name| src.starts_with(*name))
+                .max_by_key(|name| name.len())
+                .and_then(|name| Some((Token::Symbol(greek_letter(name)?), name.len())));
+
+            match (symbol, greek) {
+                (Some(symbol), Some(greek)) if greek.1 > symbol.1 => greek,
+                (Some(symbol), _) => symbol,
+                (None, Some(greek)) => greek,
+                (None, None) => (Token::Symbol(ch), ch.len_utf8()),
+            }
+        };
+
+        tokens.push(token);
+        src = src[len..].trim_start();
+    }
+
+    tokens
+}
+
+/// The argument of `text(...)`, which is not parsed as math, and the length
+/// of the whole call
+fn text_argument(src: &str) -> Option<(&str, usize)> {
+    let rest = src.strip_prefix("text")?.trim_start().strip_prefix('(')?;
+    let len = rest.find(')').unwrap_or(rest.len());
+    let consumed = src.len() - rest.len() + (len + 1).min(rest.len());
+    Some((&rest[..len], consumed))
+}
+
+/// Where a list ends
+#[derive(Clone, Copy, PartialEq)]
+enum Stop {
+    End,
+    /// At a closing bracket
+    Right,
+    /// At the given bar, e.g. the second `|` of `|x|`
+    Bar(char),
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn list<G: common::Glyph>(&mut self, stop: Stop) -> MathList<G> {
+        let mut builder = Builder::default();
+
+        while let Some(token) = self.peek() {
+            match (token, stop) {
+                (Token::Right(_), Stop::Right | Stop::Bar(_)) => break,
+                (Token::Bar(bar), Stop::Bar(expected)) if bar == expected => break,
+                _ => {}
+            }
+
+            let Some(mut atom) = self.intermediate() else {
+                // Closing brackets and scripts without a matching counterpart
+                self.pos += 1;
+                let ch = match token {
+                    Token::Right(ch) => ch,
+                    Token::Sub => Some('_'),
+                    Token::Sup => Some('^'),
+                    _ => Some('/'),
+                };
+                if let Some(ch) = ch {
+                    let (atom_type, field) = ParserImp::handle_char(ch);
+                    builder.add_atom(Atom::new(atom_type, field));
+                }
+                continue;
+            };
+
+            if self.peek() == Some(Token::Slash) {
+                self.pos += 1;
+                let numerator = ungrouped(atom);
+                let denominator = self.intermediate().map_or(Field::Empty, ungrouped);
+                atom = Atom::new(
+                    AtomType::Inner,
+                    Field::Fraction(numerator.into(), denominator.into()),
+                );
+            }
+            builder.add_atom(atom);
+        }
+
+        builder.finish()
+    }
+
+    /// A simple expression with optional scripts, e.g. `x_1^2`
+    fn intermediate<G: common::Glyph>(&mut self) -> Option<Atom<G>> {
+        let (atom_type, nucleus) = self.simple()?;
+        let mut atom = Atom::new(atom_type, nucleus);
+
+        loop {
+            match self.peek() {
+                Some(Token::Sub) if atom.subscript.is_empty() => {
+                    self.pos += 1;
+                    atom.subscript = self.argument();
+                }
+                Some(Token::Sup) if atom.superscript.is_empty() => {
+                    self.pos += 1;
+                    atom.superscript = self.argument();
+                }
+                _ => break,
+            }
+        }
+
+        Some(atom)
+    }
+
+    /// The argument of a function or script, without brackets
+    fn argument<G: common::Glyph>(&mut self) -> Field<G> {
+        self.simple()
+            .map_or(Field::Empty, |(_, field)| remove_brackets(field))
+    }
+
+    fn simple<G: common::Glyph>(&mut self) -> Option<(AtomType, Field<G>)> {
+        let token = self.peek()?;
+        if matches!(
+            token,
+            Token::Right(_) | Token::Sub | Token::Sup | Token::Slash
+        ) {
+            return None;
+        }
+        self.pos += 1;
+
+        Some(match token {
+            Token::Symbol(ch) => ParserImp::handle_char(ch),
+            Token::Number(number) => (AtomType::Ord, ParserImp::text_field(number, Family::Roman)),
+            Token::Word(name) => (AtomType::Ord, ParserImp::text_field(name, Family::Roman)),
+            Token::Limit(name) => (AtomType::Op, ParserImp::text_field(name, Family::Roman)),
+            Token::Text(text) => (AtomType::Ord, ParserImp::text_field(text, Family::Roman)),
+            Token::Left(left) => {
+                let list = self.list(Stop::Right);
+                let right = match self.peek() {
+                    Some(Token::Right(right)) => {
+                        self.pos += 1;
+                        right
+                    }
+                    _ => None,
+                };
+                let atom_type = match left.is_some() || right.is_some() {
+                    true => AtomType::Inner,
+                    false => AtomType::Ord,
+                };
+                (
+                    atom_type,
+                    Field::MathList(left.map(delimiter), list, right.map(delimiter)),
+                )
+            }
+            Token::Bar(bar) => {
+                let start = self.pos;
+                let list = self.list(Stop::Bar(bar));
+                if self.peek() == Some(Token::Bar(bar)) {
+                    self.pos += 1;
+                    let field = Field::MathList(Some(delimiter(bar)), list, Some(delimiter(bar)));
+                    (AtomType::Inner, field)
+                } else {
+                    // Without a matching bar, it is a symbol on its own
+                    self.pos = start;
+                    ParserImp::handle_char(bar)
+                }
+            }
+            Token::Sqrt => (AtomType::Ord, Field::Radical(self.argument().into())),
+            Token::Font(family) => {
+                let mut field = self.argument();
+                field.set_family(family);
+                (AtomType::Ord, field)
+            }
+            Token::Fenced(left, right) => {
+                let content = into_list(self.argument());
+                let field = Field::MathList(Some(delimiter(left)), content, Some(delimiter(right)));
+                (AtomType::Inner, field)
+            }
+            Token::Frac => {
+                let numerator = self.argument();
+                let denominator = self.argument();
+                let field = Field::Fraction(numerator.into(), denominator.into());
+                (AtomType::Inner, field)
+            }
+            Token::Unsupported(name) => (AtomType::Ord, ParserImp::make_error_field(&[name])),
+            Token::Right(_) | Token::Sub | Token::Sup | Token::Slash => unreachable!(),
+        })
+    }
+}
+
+fn delimiter(ch: char) -> Delimiter {
+    Delimiter {
+        ch,
+        color: Color::Normal,
+    }
+}
+
+/// Removes the brackets which only group, e.g. the numerator in `(a+b)/2`
+fn remove_brackets<G: common::Glyph>(field: Field<G>) -> Field<G> {
+    match field {
+        Field::MathList(Some(left), list, Some(right))
+            if matches!(left.ch, '(' | '[' | '{') && matches!(right.ch, ')' | ']' | '}') =>
+        {
+            Field::MathList(None, list, None)
+        }
+        field => field,
+    }
+}
+
+/// An atom as a single field, without grouping brackets
+fn ungrouped<G: common::Glyph>(atom: Atom<G>) -> Field<G> {
+    if atom.subscript.is_empty() && atom.superscript.is_empty() {
+        return remove_brackets(atom.nucleus);
+    }
+
+    let mut builder = Builder::default();
+    builder.add_atom(atom);
+    Field::MathList(None, builder.finish(), None)
+}
+
+fn into_list<G: common::Glyph>(field: Field<G>) -> MathList<G> {
+    match field {
+        Field::MathList(None, list, None) => list,
+        field => {
+            let mut builder = Builder::default();
+            if !field.is_empty() {
+                builder.add_atom(Atom::new(AtomType::Ord, field));
+            }
+            builder.finish()
+        }
+    }
+}
@@ -2,6 +2,8 @@ pub enum ErrorKind {
     NomError,
     InvalidDelimiter,
     UnsupportedCommand,
+    /// Malformed XML, e.g. a mismatched end tag
+    InvalidMarkup,
 }
 
 impl From<nom::error::ErrorKind> for ErrorKind {
@@ -29,6 +31,13 @@ impl<I> nom::error::ParseError<I> for Error<I> {
     }
 }
 
+impl Error<&str> {
+    /// Byte offset of the error in `src`
+    pub fn position(&self, src: &str) -> usize {
+        src.len() - self.input.len()
+    }
+}
+
 pub fn make_recoverable_error<T>(input: &str, kind: ErrorKind) -> super::ParseResult<'_, T> {
     Err(nom::Err::Error(Error { input, kind }))
 }
@@ -0,0 +1,463 @@
+//! Parser for Presentation MathML, e.g. from XML publishing pipelines. The
+//! result is the same math list the LaTeX parser builds. Unsupported elements
+//! (e.g. `mlabeledtr` or `mroot`) show their name in the error color, like
+//! unsupported commands.
+//!
+//! Only the predefined XML entities and character references are supported,
+//! named MathML entities like `&sum;` are not.
+
+use super::error::{make_recoverable_error, ErrorKind};
+use super::{ParseResult, ParserImp};
+use crate::common::{self, Color, Family};
+use crate::mathlist::{Atom, AtomType, Builder, Delimiter, Field, MathList};
+use nom::{bytes::complete::tag, character::complete, Parser};
+
+pub fn parse<G: common::Glyph>(src: &str) -> crate::Result<MathList<G>> {
+    let document = match document(src) {
+        Ok((_, document)) => document,
+        Err(nom::Err::Error(err) | nom::Err::Failure(err)) => {
+            return Err(crate::Error::Parse {
+                position: err.position(src),
+            })
+        }
+        Err(nom::Err::Incomplete(_)) => {
+            return Err(crate::Error::Parse {
+                position: src.len(),
+            })
+        }
+    };
+
+    // The root is usually `<math>`, but single elements are accepted as well
+    let mut elements: Vec<_> = match document.name {
+        "math" => document.elements().collect(),
+        _ => vec![&document],
+    };
+
+    // Unwrap `<semantics>` and a single `<mrow>` without fences
+    while let [single] = elements[..] {
+        let has_fences = || {
+            single
+                .elements()
+                .any(|child| child.attribute("fence").is_some())
+        };
+        elements = match single.name {
+            "semantics" => single.elements().take(1).collect(),
+            "mrow" if !has_fences() => single.elements().collect(),
+            _ => break,
+        };
+    }
+
+    Ok(list_of(elements))
+}
+
+struct Element<'a> {
+    /// The local name, without namespace prefix
+    name: &'a str,
+    attributes: Vec<(&'a str, String)>,
+    children: Vec<Content<'a>>,
+}
+
+enum Content<'a> {
+    Element(Element<'a>),
+    Text(String),
+}
+
+impl Element<'_> {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &Element<'_>> {
+        self.children.iter().filter_map(|child| match child {
+            Content::Element(element) => Some(element),
+            Content::Text(_) => None,
+        })
+    }
+
+    /// The text content, without leading and trailing whitespace
+    fn text(&self) -> String {
+        let mut result = String::new();
+        for child in &self.children {
+            match child {
+                Content::Element(element) => result.push_str(&element.text()),
+                Content::Text(text) => result.push_str(text),
+            }
+        }
+        result.trim().to_owned()
+    }
+}
+
+fn whitespace(src: &str) -> ParseResult<'_, ()> {
+    ParserImp::<common::NoGlyph>::whitespace(src)
+}
+
+/// Skips whitespace, comments, processing instructions and doctype
+fn misc(src: &str) -> ParseResult<'_, ()> {
+    let skip = |start, end| {
+        nom::sequence::tuple((tag(start), nom::bytes::complete::take_until(end), tag(end)))
+            .map(|_| ())
+    };
+
+    let (src, _) = whitespace(src)?;
+    let (src, _) = nom::multi::many0(nom::sequence::terminated(
+        nom::branch::alt((skip("<!--", "-->"), skip("<?", "?>"), skip("<!", ">"))),
+        whitespace,
+    ))(src)?;
+    Ok((src, ()))
+}
+
+fn document(src: &str) -> ParseResult<'_, Element<'_>> {
+    let (src, ()) = misc(src)?;
+    let (src, element) = element(src)?;
+    let (src, ()) = misc(src)?;
+    let (src, _) = nom::combinator::eof(src)?;
+    Ok((src, element))
+}
+
+fn name(src: &str) -> ParseResult<'_, &str> {
+    nom::bytes::complete::take_while1(|ch: char| {
+        ch.is_alphanumeric() || matches!(ch, '-' | '_' | ':' | '.')
+    })(src)
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn attribute(src: &str) -> ParseResult<'_, (&str, String)> {
+    let (src, _) = complete::multispace1(src)?;
+    let (src, key) = name(src)?;
+    let (src, _) = nom::sequence::tuple((whitespace, complete::char('='), whitespace))(src)?;
+
+    let quoted = |quote| {
+        nom::sequence::delimited(
+            complete::char(quote),
+            nom::bytes::complete::take_till(move |ch| ch == quote),
+            complete::char(quote),
+        )
+    };
+    let (remaining, value) = nom::branch::alt((quoted('"'), quoted('\'')))(src)?;
+    match unescape(value) {
+        Some(value) => Ok((remaining, (local_name(key), value))),
+        None => make_recoverable_error(src, ErrorKind::InvalidMarkup),
+    }
+}
+
+fn element(src: &str) -> ParseResult<'_, Element<'_>> {
+    let (src, _) = complete::char('<')(src)?;
+    let (src, tag_name) = name(src)?;
+    let (src, attributes) = nom::multi::many0(attribute)(src)?;
+    let (src, _) = whitespace(src)?;
+
+    let mut element = Element {
+        name: local_name(tag_name),
+        attributes,
+        children: Vec::new(),
+    };
+
+    if let Ok((src, _)) = tag::<_, _, super::error::Error<_>>("/>")(src) {
+        return Ok((src, element));
+    }
+    let (mut src, _) = complete::char('>')(src)?;
+
+    loop {
+        if let Some(remaining) = src.strip_prefix("</") {
+            let (remaining, end_name) = name(remaining)?;
+            if end_name != tag_name {
+                return make_recoverable_error(src, ErrorKind::InvalidMarkup);
+            }
+            let (remaining, _) = nom::sequence::pair(whitespace, complete::char('>'))(remaining)?;
+            return Ok((remaining, element));
+        }
+
+        let (remaining, ()) = misc(src)?;
+        if remaining.starts_with("</") {
+            src = remaining;
+            continue;
+        }
+        if remaining.starts_with('<') {
+            let (remaining, child) = self::element(remaining)?;
+            element.children.push(Content::Element(child));
+            src = remaining;
+            continue;
+        }
+
+        let (remaining, text) = nom::bytes::complete::take_till1(|ch| ch == '<')(src)?;
+        let Some(text) = unescape(text) else {
+            return make_recoverable_error(src, ErrorKind::InvalidMarkup);
+        };
+        element.children.push(Content::Text(text));
+        src = remaining;
+    }
+}
+
+/// Replaces entities and character references
+fn unescape(text: &str) -> Option<String> {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        let end = rest[start..].find(';')? + start;
+        let ch = match &rest[start + 1..end] {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            reference => {
+                let code = match reference.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => reference.strip_prefix('#')?.parse().ok()?,
+                };
+                char::from_u32(code)?
+            }
+        };
+        result.push(ch);
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    Some(result)
+}
+
+/// The children of an element, as in an `<mrow>`
+fn list<G: common::Glyph>(element: &Element) -> MathList<G> {
+    list_of(element.elements())
+}
+
+fn list_of<'a, 'b: 'a, G: common::Glyph>(
+    elements: impl IntoIterator<Item = &'a Element<'b>>,
+) -> MathList<G> {
+    let mut builder = Builder::default();
+    for element in elements {
+        if let Some(atom) = atom(element) {
+            builder.add_atom(atom);
+        }
+    }
+    builder.finish()
+}
+
+/// An element as a single field, e.g. the numerator of a fraction
+fn field<G: common::Glyph>(element: &Element) -> Field<G> {
+    match atom(element) {
+        None => Field::Empty,
+        Some(Atom {
+            atom_type: AtomType::Ord,
+            nucleus,
+            subscript: Field::Empty,
+            superscript: Field::Empty,
+        }) => nucleus,
+        Some(atom) => {
+            let mut builder = Builder::default();
+            builder.add_atom(atom);
+            Field::MathList(None, builder.finish(), None)
+        }
+    }
+}
+
+/// The content of a table cell
+fn cell<G: common::Glyph>(element: &Element) -> Field<G> {
+    match element.name {
+        "mtd" => Field::MathList(None, list(element), None),
+        _ => field(element),
+    }
+}
+
+/// Shows the name of an unsupported or malformed element
+fn error<G: common::Glyph>(element: &Element) -> Atom<G> {
+    Atom::new(AtomType::Ord, ParserImp::make_error_field(&[element.name]))
+}
+
+fn mathvariant(element: &Element, default: Family) -> Family {
+    match element.attribute("mathvariant") {
+        Some("normal") => Family::Roman,
+        Some("italic") => Family::Italic,
+        Some("bold") => Family::Bold,
+        Some("sans-serif") => Family::SansSerif,
+        Some("monospace") => Family::Monospace,
+        _ => default,
+    }
+}
+
+fn operator<G: common::Glyph>(text: &str) -> Option<Atom<G>> {
+    let mut chars = text.chars();
+    let (Some(ch), None) = (chars.next(), chars.next()) else {
+        // Multi-letter operators like `lim`
+        return Some(Atom::new(
+            AtomType::Op,
+            ParserImp::text_field(text, Family::Roman),
+        ));
+    };
+
+    // Invisible operators, e.g. `&#x2062;` (invisible times), and spaces
+    if ('\u{2061}'..='\u{2064}').contains(&ch) || ch.is_whitespace() {
+        return None;
+    }
+
+    // Classified like characters in LaTeX source
+    let (atom_type, field) = ParserImp::handle_char(ch);
+    Some(Atom::new(atom_type, field))
+}
+
+fn delimiter(text: &str) -> Option<Delimiter> {
+    let ch = text.trim().chars().next()?;
+    Some(Delimiter {
+        ch,
+        color: Color::Normal,
+    })
+}
+
+fn atom<G: common::Glyph>(element: &Element) -> Option<Atom<G>> {
+    let children: Vec<_> = element.elements().collect();
+
+    Some(match element.name {
+        "mi" => {
+            let text = element.text();
+            // Single letters are italic, names like `sin` upright
+            let default = match text.chars().count() {
+                1 => super::default_family(text.chars().next().unwrap_or(' ')),
+                _ => Family::Roman,
+            };
+            Atom::new(
+                AtomType::Ord,
+                ParserImp::text_field(&text, mathvariant(element, default)),
+            )
+        }
+        "mn" | "mtext" | "ms" => Atom::new(
+            AtomType::Ord,
+            ParserImp::text_field(&element.text(), mathvariant(element, Family::Roman)),
+        ),
+        "mo" => return operator(&element.text()),
+        "mspace" | "annotation" | "annotation-xml" | "mprescripts" | "none" => return None,
+        "mrow" => {
+            // Fences at the start and the end are delimiters, like `\left(`
+            let fence = |child: Option<&&Element>, form| {
+                let child = child.filter(|child| {
+                    child.name == "mo"
+                        && child.attribute("fence") == Some("true")
+                        && child.attribute("form") != Some(form)
+                })?;
+                delimiter(&child.text())
+            };
+            let mut content = &children[..];
+            let left = fence(content.first(), "postfix");
+            if left.is_some() {
+                content = &content[1..];
+            }
+            let right = fence(content.last(), "prefix");
+            if right.is_some() {
+                content = &content[..content.len() - 1];
+            }
+
+            let atom_type = match left.is_some() || right.is_some() {
+                true => AtomType::Inner,
+                false => AtomType::Ord,
+            };
+            Atom::new(
+                atom_type,
+                Field::MathList(left, list_of(content.iter().copied()), right),
+            )
+        }
+        "mstyle" | "mpadded" | "mphantom" | "menclose" | "math" => {
+            Atom::new(AtomType::Ord, Field::MathList(None, list(element), None))
+        }
+        "semantics" => return atom(children.first()?),
+        "merror" => {
+            let mut builder = Builder::default();
+            for ch in element.text().chars().filter(|ch| !ch.is_whitespace()) {
+                builder.add_symbol(ch, Color::Error);
+            }
+            Atom::new(AtomType::Ord, Field::MathList(None, builder.finish(), None))
+        }
+        "mfrac" => {
+            let [numerator, denominator] = children[..] else {
+                return Some(error(element));
+            };
+            let field = Field::Fraction(field(numerator).into(), field(denominator).into());
+            Atom::new(AtomType::Inner, field)
+        }
+        "msqrt" => {
+            let radicand = Field::MathList(None, list(element), None);
+            Atom::new(AtomType::Ord, Field::Radical(radicand.into()))
+        }
+        "msub" | "msup" | "msubsup" | "munder" | "mover" | "munderover" => {
+            let Some((base, scripts)) = children.split_first() else {
+                return Some(error(element));
+            };
+            let expected =
+                if element.name.ends_with("subsup") || element.name.ends_with("underover") {
+                    2
+                } else {
+                    1
+                };
+            if scripts.len() != expected {
+                return Some(error(element));
+            }
+
+            let mut base = match atom(base) {
+                Some(atom) if atom.subscript.is_empty() && atom.superscript.is_empty() => atom,
+                base => {
+                    let mut builder = Builder::default();
+                    builder.add_atom(base?);
+                    Atom::new(AtomType::Ord, Field::MathList(None, builder.finish(), None))
+                }
+            };
+
+            // Scripts below and above are limits, which are only placed on
+            // operators
+            if matches!(element.name, "munder" | "mover" | "munderover") {
+                base.atom_type = AtomType::Op;
+            }
+            match element.name {
+                "msub" | "munder" => base.subscript = field(scripts[0]),
+                "msup" | "mover" => base.superscript = field(scripts[0]),
+                _ => {
+                    base.subscript = field(scripts[0]);
+                    base.superscript = field(scripts[1]);
+                }
+            }
+            base
+        }
+        "mtable" => {
+            // Rows and cells without their `mtr` and `mtd` element are
+            // accepted as well
+            let rows = children
+                .iter()
+                .map(|row| match row.name {
+                    "mtr" => row.elements().map(cell).collect(),
+                    _ => vec![cell(row)],
+                })
+                .collect();
+            Atom::new(AtomType::Ord, Field::Table(rows))
+        }
+        "mfenced" => {
+            let open = element.attribute("open").unwrap_or("(");
+            let close = element.attribute("close").unwrap_or(")");
+            let separators: Vec<char> = element
+                .attribute("separators")
+                .unwrap_or(",")
+                .chars()
+                .filter(|ch| !ch.is_whitespace())
+                .collect();
+
+            let mut builder = Builder::default();
+            for (idx, child) in children.iter().enumerate() {
+                if idx > 0 {
+                    let separator = separators.get(idx - 1).or(separators.last());
+                    if let Some(atom) = separator.and_then(|ch| operator(&ch.to_string())) {
+                        builder.add_atom(atom);
+                    }
+                }
+                if let Some(atom) = atom(child) {
+                    builder.add_atom(atom);
+                }
+            }
+
+            let field = Field::MathList(delimiter(open), builder.finish(), delimiter(close));
+            Atom::new(AtomType::Inner, field)
+        }
+        _ => error(element),
+    })
+}
@@ -51,3 +51,7 @@ pub fn command_to_char(cmd: &str) -> Option<char> {
     let lookup = generated::CHAR_COMMANDS.binary_search_by_key(&cmd, |(cmd, _)| cmd);
     lookup.map(|idx| generated::CHAR_COMMANDS[idx].1).ok()
 }
+
+pub fn char_commands() -> impl Iterator<Item = (&'static str, char)> {
+    generated::CHAR_COMMANDS.iter().copied()
+}
@@ -653,7 +653,7 @@ pub static CHAR_CLASSIFICATION: [(u32, CharClassification); 650] = [
     ('🞟' as u32 + 1, CharClassification::Ignore),
 ];
 
-pub static CHAR_COMMANDS: [(&'static str, char); 2446] = [
+pub static CHAR_COMMANDS: [(&str, char); 2446] = [
     ("APLboxquestion", '⍰'),
     ("APLboxupcaret", '⍓'),
     ("APLnotbackslash", '⍀'),
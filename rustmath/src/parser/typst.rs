@@ -0,0 +1,541 @@
+//! Parser for Typst math, e.g. `sum_(i=0)^n frac(a, b) + sqrt(x)`. Names of
+//! symbols are looked up in Typst's notation (`arrow.r`), then as the Unicode
+//! math commands of the LaTeX parser, which share many names with Typst
+//! (`nabla`, `subset`), and as Greek letters. Unknown functions and
+//! variables make [`parse`] fail. [`parse_with_diagnostics`] reports them
+//! instead and shows them in the error color.
+
+use super::{greek_letter, number_len, tables, ParserImp};
+use crate::common::{self, Color, Family};
+use crate::mathlist::{Atom, AtomType, Builder, Delimiter, Field, MathList};
+use std::ops::Range;
+
+/// A problem in the source, e.g. an unknown function
+#[derive(Clone, PartialEq, Debug)]
+pub struct Diagnostic {
+    /// Byte range in the source
+    pub range: Range<usize>,
+    pub message: String,
+}
+
+impl core::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} at position {}", self.message, self.range.start)
+    }
+}
+
+/// Fails at the first problem in the source
+pub fn parse<G: common::Glyph>(src: &str) -> crate::Result<MathList<G>> {
+    let (list, diagnostics) = parse_with_diagnostics(src);
+    match diagnostics.first() {
+        Some(diagnostic) => Err(crate::Error::Parse {
+            position: diagnostic.range.start,
+        }),
+        None => Ok(list),
+    }
+}
+
+/// Like [`parse`], but also returns the problems in the source
+pub fn parse_with_diagnostics<G: common::Glyph>(src: &str) -> (MathList<G>, Vec<Diagnostic>) {
+    let mut parser = Parser {
+        tokens: tokenize(src),
+        pos: 0,
+        diagnostics: Vec::new(),
+    };
+    let list = parser.list(Stop::End);
+    (list, parser.diagnostics)
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Token<'a> {
+    Symbol(char),
+    /// Names of symbols, functions and variables, e.g. `alpha` or `arrow.r`
+    Ident(&'a str),
+    Number(&'a str),
+    Text(&'a str),
+    Left(char),
+    Right(char),
+    Comma,
+    Sub,
+    Sup,
+    Slash,
+}
+
+/// Shorthands for symbols, e.g. `->` for `arrow.r`
+const SHORTHANDS: &[(&str, char)] = &[
+    ("->", '→'),
+    ("<-", '←'),
+    ("<->", '↔'),
+    ("=>", '⇒'),
+    ("<==", '⟸'),
+    ("==>", '⟹'),
+    ("|->", '↦'),
+    ("<=", '≤'),
+    (">=", '≥'),
+    ("!=", '≠'),
+    ("<<", '≪'),
+    (">>", '≫'),
+    (":=", '≔'),
+    ("...", '…'),
+    ("'", '′'),
+    ("*", '∗'),
+    ("-", '−'),
+    ("~", '∼'),
+    ("||", '‖'),
+];
+
+/// Symbols whose Typst names differ from the Unicode math commands
+const SYMBOLS: &[(&str, char)] = &[
+    ("infinity", '∞'),
+    ("oo", '∞'),
+    ("integral", '∫'),
+    ("integral.double", '∬'),
+    ("integral.cont", '∮'),
+    ("product", '∏'),
+    ("dot", '⋅'),
+    ("dot.op", '⋅'),
+    ("dots", '…'),
+    ("dots.h", '…'),
+    ("dots.c", '⋯'),
+    ("dots.v", '⋮'),
+    ("dots.down", '⋱'),
+    ("plus", '+'),
+    ("minus", '−'),
+    ("plus.minus", '±'),
+    ("minus.plus", '∓'),
+    ("eq", '='),
+    ("eq.not", '≠'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("lt.eq", '≤'),
+    ("gt.eq", '≥'),
+    ("in.not", '∉'),
+    ("subset.eq", '⊆'),
+    ("supset.eq", '⊇'),
+    ("union", '∪'),
+    ("sect", '∩'),
+    ("and", '∧'),
+    ("or", '∨'),
+    ("not", '¬'),
+    ("arrow.r", '→'),
+    ("arrow.l", '←'),
+    ("arrow.l.r", '↔'),
+    ("arrow.t", '↑'),
+    ("arrow.b", '↓'),
+    ("arrow.r.double", '⇒'),
+    ("arrow.l.double", '⇐'),
+    ("arrow.l.r.double", '⇔'),
+    ("arrow.r.bar", '↦'),
+    ("angle.l", '⟨'),
+    ("angle.r", '⟩'),
+    ("bar.v", '|'),
+    ("bar.v.double", '‖'),
+    ("degree", '°'),
+    ("prime", '′'),
+    ("planck.reduce", 'ℏ'),
+    ("NN", 'ℕ'),
+    ("ZZ", 'ℤ'),
+    ("QQ", 'ℚ'),
+    ("RR", 'ℝ'),
+    ("CC", 'ℂ'),
+];
+
+/// Predefined operators, e.g. `sin`. Operators with limits have `true`
+const OPERATORS: &[(&str, bool)] = &[
+    ("arccos", false),
+    ("arcsin", false),
+    ("arctan", false),
+    ("arg", false),
+    ("cos", false),
+    ("cosh", false),
+    ("cot", false),
+    ("coth", false),
+    ("csc", false),
+    ("deg", false),
+    ("det", true),
+    ("dim", false),
+    ("exp", false),
+    ("gcd", true),
+    ("hom", false),
+    ("inf", true),
+    ("ker", false),
+    ("lg", false),
+    ("lim", true),
+    ("liminf", true),
+    ("limsup", true),
+    ("ln", false),
+    ("log", false),
+    ("max", true),
+    ("min", true),
+    ("mod", false),
+    ("Pr", true),
+    ("sec", false),
+    ("sin", false),
+    ("sinh", false),
+    ("sup", true),
+    ("tan", false),
+    ("tanh", false),
+];
+
+fn symbol(name: &str) -> Option<char> {
+    let symbol = SYMBOLS.iter().find(|(symbol, _)| *symbol == name);
+    if let Some((_, ch)) = symbol {
+        return Some(*ch);
+    }
+
+    // Commands of accents like `\bar` are no symbols
+    let ch = tables::command_to_char(name).or_else(|| greek_letter(name))?;
+    match tables::CharClassification::classify(ch) {
+        tables::CharClassification::Diacritic => None,
+        classification => classification.to_atom_type().map(|_| ch),
+    }
+}
+
+fn tokenize(src: &str) -> Vec<(Token<'_>, Range<usize>)> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while let Some(ch) = src[pos..].chars().next() {
+        let rest = &src[pos..];
+        let ident_len = |rest: &str| {
+            rest.find(|ch: char| !ch.is_alphabetic())
+                .unwrap_or(rest.len())
+        };
+
+        let (token, len) = if ch.is_whitespace() || ch == '&' {
+            // Alignment points are ignored
+            pos += ch.len_utf8();
+            continue;
+        } else if rest.starts_with("//") {
+            pos += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        } else if let Some(escaped) = rest.strip_prefix('\\') {
+            // A backslash escapes a symbol, on its own it is a line break
+            match escaped.chars().next() {
+                Some(ch) if !ch.is_whitespace() => (Token::Symbol(ch), 1 + ch.len_utf8()),
+                _ => {
+                    pos += 1;
+                    continue;
+                }
+            }
+        } else if let Some(text) = rest.strip_prefix('"') {
+            let len = text.find('"').unwrap_or(text.len());
+            (Token::Text(&text[..len]), (len + 2).min(rest.len()))
+        } else if ch.is_ascii_digit() {
+            let len = number_len(rest);
+            (Token::Number(&rest[..len]), len)
+        } else if ch.is_alphabetic() && ident_len(rest) > 1 {
+            // Identifiers with modifiers, e.g. `arrow.r.double`
+            let mut len = ident_len(rest);
+            while let Some(modifier) = rest[len..].strip_prefix('.') {
+                match ident_len(modifier) {
+                    0 => break,
+                    modifier_len => len += 1 + modifier_len,
+                }
+            }
+            (Token::Ident(&rest[..len]), len)
+        } else {
+            let shorthand = SHORTHANDS
+                .iter()
+                .filter(|(shorthand, _)| rest.starts_with(shorthand))
+                .max_by_key(|(shorthand, _)| shorthand.len());
+            match (shorthand, ch) {
+                (Some((shorthand, ch)), _) => (Token::Symbol(*ch), shorthand.len()),
+                (None, '(' | '[' | '{') => (Token::Left(ch), 1),
+                (None, ')' | ']' | '}') => (Token::Right(ch), 1),
+                (None, ',') => (Token::Comma, 1),
+                (None, '_') => (Token::Sub, 1),
+                (None, '^') => (Token::Sup, 1),
+                (None, '/') => (Token::Slash, 1),
+                (None, _) => (Token::Symbol(ch), ch.len_utf8()),
+            }
+        };
+
+        tokens.push((token, pos..pos + len));
+        pos += len;
+    }
+
+    tokens
+}
+
+/// Where a list ends
+#[derive(Clone, Copy, PartialEq)]
+enum Stop {
+    End,
+    /// At a closing bracket
+    Right,
+    /// At the end of a function argument
+    Argument,
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token<'a>, Range<usize>)>,
+    pos: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).map(|(token, _)| *token)
+    }
+
+    fn range(&self, pos: usize) -> Range<usize> {
+        self.tokens[pos].1.clone()
+    }
+
+    fn report(&mut self, range: Range<usize>, message: String) {
+        self.diagnostics.push(Diagnostic { range, message });
+    }
+
+    fn list<G: common::Glyph>(&mut self, stop: Stop) -> MathList<G> {
+        let mut builder = Builder::default();
+
+        while let Some(token) = self.peek() {
+            match (token, stop) {
+                (Token::Right(_), Stop::Right) => break,
+                (Token::Right(')') | Token::Comma, Stop::Argument) => break,
+                _ => {}
+            }
+
+            let Some(mut atom) = self.attachment() else {
+                // Closing brackets, commas and scripts on their own
+                self.pos += 1;
+                let ch = match token {
+                    Token::Right(ch) => ch,
+                    Token::Comma => ',',
+                    Token::Sub => '_',
+                    Token::Sup => '^',
+                    _ => '/',
+                };
+                let (atom_type, field) = ParserImp::handle_char(ch);
+                builder.add_atom(Atom::new(atom_type, field));
+                continue;
+            };
+
+            if self.peek() == Some(Token::Slash) {
+                self.pos += 1;
+                let numerator = ungrouped(atom);
+                let denominator = self.attachment().map_or(Field::Empty, ungrouped);
+                atom = Atom::new(
+                    AtomType::Inner,
+                    Field::Fraction(numerator.into(), denominator.into()),
+                );
+            }
+            builder.add_atom(atom);
+        }
+
+        builder.finish()
+    }
+
+    /// A unit with optional scripts, e.g. `x_1^2`
+    fn attachment<G: common::Glyph>(&mut self) -> Option<Atom<G>> {
+        let (atom_type, nucleus) = self.unit()?;
+        let mut atom = Atom::new(atom_type, nucleus);
+
+        loop {
+            match self.peek() {
+                Some(Token::Sub) if atom.subscript.is_empty() => {
+                    self.pos += 1;
+                    atom.subscript = self.script();
+                }
+                Some(Token::Sup) if atom.superscript.is_empty() => {
+                    self.pos += 1;
+                    atom.superscript = self.script();
+                }
+                _ => break,
+            }
+        }
+
+        Some(atom)
+    }
+
+    /// A script, without parentheses
+    fn script<G: common::Glyph>(&mut self) -> Field<G> {
+        self.unit()
+            .map_or(Field::Empty, |(_, field)| remove_parens(field))
+    }
+
+    fn unit<G: common::Glyph>(&mut self) -> Option<(AtomType, Field<G>)> {
+        let token = self.peek()?;
+        let start = self.pos;
+        if matches!(
+            token,
+            Token::Right(_) | Token::Comma | Token::Sub | Token::Sup | Token::Slash
+        ) {
+            return None;
+        }
+        self.pos += 1;
+
+        Some(match token {
+            Token::Symbol(ch) => ParserImp::handle_char(ch),
+            Token::Number(number) => (AtomType::Ord, ParserImp::text_field(number, Family::Roman)),
+            Token::Text(text) => (AtomType::Ord, ParserImp::text_field(text, Family::Roman)),
+            Token::Left(left) => {
+                let list = self.list(Stop::Right);
+                let right = match self.peek() {
+                    Some(Token::Right(right)) => {
+                        self.pos += 1;
+                        Some(delimiter(right))
+                    }
+                    _ => None,
+                };
+                (
+                    AtomType::Inner,
+                    Field::MathList(Some(delimiter(left)), list, right),
+                )
+            }
+            Token::Ident(name) => {
+                let is_call = matches!(self.tokens.get(self.pos), Some((Token::Left('('), range))
+                    if range.start == self.range(start).end);
+                match is_call {
+                    true => self.call(name, start),
+                    false => self.identifier(name, start),
+                }
+            }
+            Token::Right(_) | Token::Comma | Token::Sub | Token::Sup | Token::Slash => {
+                unreachable!()
+            }
+        })
+    }
+
+    fn identifier<G: common::Glyph>(&mut self, name: &str, start: usize) -> (AtomType, Field<G>) {
+        if let Some((_, limits)) = OPERATORS.iter().find(|(operator, _)| *operator == name) {
+            let atom_type = match limits {
+                true => AtomType::Op,
+                false => AtomType::Ord,
+            };
+            return (atom_type, ParserImp::text_field(name, Family::Roman));
+        }
+        if let Some(ch) = symbol(name) {
+            return ParserImp::handle_char(ch);
+        }
+
+        self.report(self.range(start), format!("unknown variable `{}`", name));
+        (AtomType::Ord, ParserImp::make_error_field(&[name]))
+    }
+
+    /// A function call, the position is at the opening parenthesis
+    fn call<G: common::Glyph>(&mut self, name: &str, start: usize) -> (AtomType, Field<G>) {
+        let expected = match name {
+            "frac" => Some(2),
+            "sqrt" | "abs" | "norm" | "floor" | "ceil" | "upright" | "italic" | "bold" | "sans"
+            | "mono" | "op" | "lr" => Some(1),
+            "vec" => None,
+            _ => {
+                // The arguments follow as a group
+                self.report(self.range(start), format!("unknown function `{}`", name));
+                return (AtomType::Ord, ParserImp::make_error_field(&[name]));
+            }
+        };
+
+        self.pos += 1;
+        let mut arguments = Vec::new();
+        loop {
+            arguments.push(self.list::<G>(Stop::Argument));
+            match self.peek() {
+                Some(Token::Comma) => self.pos += 1,
+                Some(Token::Right(_)) => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => break,
+            }
+        }
+
+        let end = self.range(self.pos.saturating_sub(1)).end;
+        let range = self.range(start).start..end;
+        if expected.is_some_and(|expected| expected != arguments.len()) {
+            let message = format!(
+                "`{}` expects {} argument(s), not {}",
+                name,
+                expected.unwrap_or_default(),
+                arguments.len()
+            );
+            self.report(range, message);
+            return (AtomType::Ord, ParserImp::make_error_field(&[name]));
+        }
+
+        let mut arguments = arguments.into_iter();
+        let mut argument = || {
+            arguments
+                .next()
+                .unwrap_or_else(|| Builder::default().finish())
+        };
+        let fenced = |left, content, right| {
+            let field = Field::MathList(Some(delimiter(left)), content, Some(delimiter(right)));
+            (AtomType::Inner, field)
+        };
+
+        match name {
+            "frac" => {
+                let numerator = Field::MathList(None, argument(), None);
+                let denominator = Field::MathList(None, argument(), None);
+                let field = Field::Fraction(numerator.into(), denominator.into());
+                (AtomType::Inner, field)
+            }
+            "sqrt" => {
+                let radicand = Field::MathList(None, argument(), None);
+                (AtomType::Ord, Field::Radical(radicand.into()))
+            }
+            "abs" => fenced('|', argument(), '|'),
+            "norm" => fenced('‖', argument(), '‖'),
+            "floor" => fenced('⌊', argument(), '⌋'),
+            "ceil" => fenced('⌈', argument(), '⌉'),
+            "upright" | "italic" | "bold" | "sans" | "mono" => {
+                let family = match name {
+                    "upright" => Family::Roman,
+                    "italic" => Family::Italic,
+                    "bold" => Family::Bold,
+                    "sans" => Family::SansSerif,
+                    _ => Family::Monospace,
+                };
+                let mut field = Field::MathList(None, argument(), None);
+                field.set_family(family);
+                (AtomType::Ord, field)
+            }
+            "op" => {
+                let mut field = Field::MathList(None, argument(), None);
+                field.set_family(Family::Roman);
+                (AtomType::Op, field)
+            }
+            "lr" => (AtomType::Inner, Field::MathList(None, argument(), None)),
+            _ => {
+                // `vec`, as a column
+                let rows = arguments
+                    .map(|argument| vec![Field::MathList(None, argument, None)])
+                    .collect();
+                let mut builder = Builder::default();
+                builder.add_atom(Atom::new(AtomType::Ord, Field::Table(rows)));
+                fenced('(', builder.finish(), ')')
+            }
+        }
+    }
+}
+
+fn delimiter(ch: char) -> Delimiter {
+    Delimiter {
+        ch,
+        color: Color::Normal,
+    }
+}
+
+/// Removes parentheses which only group, e.g. in `(a+b)/2`
+fn remove_parens<G: common::Glyph>(field: Field<G>) -> Field<G> {
+    match field {
+        Field::MathList(Some(left), list, Some(right)) if left.ch == '(' && right.ch == ')' => {
+            Field::MathList(None, list, None)
+        }
+        field => field,
+    }
+}
+
+/// An atom as a single field, without grouping parentheses
+fn ungrouped<G: common::Glyph>(atom: Atom<G>) -> Field<G> {
+    if atom.subscript.is_empty() && atom.superscript.is_empty() {
+        return remove_parens(atom.nucleus);
+    }
+
+    let mut builder = Builder::default();
+    builder.add_atom(atom);
+    Field::MathList(None, builder.finish(), None)
+}
@@ -1,4 +1,27 @@
+mod test_asciimath;
+mod test_errors;
 mod test_generated_sources;
+mod test_mathml;
+mod test_typst;
+
+#[cfg(all(feature = "eps", feature = "embedded-font"))]
+mod test_eps;
+
+#[cfg(feature = "svg")]
+mod test_fonts;
 
 #[cfg(feature = "png")]
 mod test_images;
+
+#[cfg(all(feature = "pdf", feature = "embedded-font"))]
+mod test_pdf;
+
+#[cfg(feature = "svg")]
+mod test_layout;
+
+#[cfg(feature = "tiny-skia")]
+mod test_options;
+
+#[cfg(all(feature = "svg", feature = "embedded-font"))]
+mod test_svg;
+mod test_text;
@@ -0,0 +1,78 @@
+use crate::parser::Syntax;
+
+/// The MathML of the formula, without the annotation
+fn mathml_body(src: &str, syntax: Syntax) -> String {
+    let mathml = crate::render_mathml_with(src, syntax).unwrap();
+    let end = mathml.find("</mrow>").unwrap();
+    mathml[..end].to_owned()
+}
+
+#[test]
+fn test_asciimath_like_latex() {
+    for (asciimath, latex) in [
+        (
+            "sum_(i=1)^n i^2 = (n(n+1)(2n+1))/6",
+            "\\sum_{i=1}^n i^2 = \\frac{n\\left(n+1\\right)\\left(2n+1\\right)}{6}",
+        ),
+        ("a+b/c", "a+\\frac{b}{c}"),
+        ("e^(-x^2/2)", "e^{-\\frac{x^2}{2}}"),
+        ("frac(1)(x_1)", "\\frac{1}{x_1}"),
+        ("|x| + ||v|| - |a", "\\left|x\\right|+\\left‖v\\right‖-|a"),
+        ("(a, b] uu {: c :}", "\\left(a,b\\right]\\cup c"),
+        (
+            "lim_(x->0) sin x",
+            "\\operatorname{lim}_{x\\rightarrow 0}\\mathrm{sin}x",
+        ),
+        ("alpha Gamma oo", "\\mitalpha\\mupGamma\\infty"),
+        ("bb x + 12.5", "\\mathbf{x}+12.5"),
+        ("a)", "a)"),
+    ] {
+        assert_eq!(
+            mathml_body(asciimath, Syntax::AsciiMath),
+            mathml_body(latex, Syntax::Latex),
+            "{}",
+            asciimath
+        );
+    }
+}
+
+#[test]
+fn test_asciimath_sqrt() {
+    let mathml = crate::render_mathml_with("sqrt(x+1) + sqrt 2", Syntax::AsciiMath).unwrap();
+    assert!(mathml.contains("<msqrt><mrow><mi>𝑥</mi><mo>+</mo><mn>1</mn></mrow></msqrt>"));
+    assert!(mathml.contains("<msqrt><mn>2</mn></msqrt>"));
+}
+
+#[test]
+fn test_asciimath_unsupported() {
+    let mathml = mathml_body("hat x", Syntax::AsciiMath);
+    assert!(mathml.contains("<merror><mtext>hat</mtext></merror><mi>𝑥</mi>"));
+
+    let mathml = crate::render_mathml_with("x/", Syntax::AsciiMath).unwrap();
+    assert!(mathml.contains("<annotation encoding=\"text/x-asciimath\">x/</annotation>"));
+}
+
+#[cfg(all(feature = "svg", feature = "embedded-font"))]
+#[test]
+fn test_render_with_syntax() {
+    let options = crate::RenderOptions::default();
+    let latex = crate::render_svg_with("\\frac{1}{2}", false, &options).unwrap();
+
+    let options = options.with_syntax(Syntax::AsciiMath);
+    let asciimath = crate::render_svg_with("1/2", false, &options).unwrap();
+    assert_eq!(asciimath, latex);
+
+    // The syntax is kept with the source in the metadata
+    let asciimath = crate::render_svg_with("1/2", true, &options).unwrap();
+    assert_eq!(
+        crate::get_source_from_metadata(asciimath.as_bytes()).unwrap(),
+        ("1/2".to_owned(), Syntax::AsciiMath)
+    );
+
+    let options = options.with_syntax(Syntax::MathMl);
+    let mathml = "<math><mfrac><mn>1</mn><mn>2</mn></mfrac></math>";
+    assert_eq!(
+        crate::render_svg_with(mathml, false, &options).unwrap(),
+        latex
+    );
+}
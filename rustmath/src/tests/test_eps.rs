@@ -0,0 +1,60 @@
+use crate::{options::Padding, parser::Syntax, RenderOptions};
+
+fn header_value<'a>(eps: &'a str, key: &str) -> &'a str {
+    eps.lines()
+        .find_map(|line| line.strip_prefix(key))
+        .unwrap()
+        .trim()
+}
+
+#[test]
+fn test_eps_bounding_box() {
+    let options = RenderOptions::default().with_padding(Padding::uniform(0.0));
+    let eps = crate::render_eps_with("\\frac{a}{b}", false, &options).unwrap();
+
+    assert!(eps.starts_with("%!PS-Adobe-3.0 EPSF-3.0\n"));
+    assert!(eps.ends_with("%%EOF\n"));
+
+    let hires: Vec<f32> = header_value(&eps, "%%HiResBoundingBox:")
+        .split(' ')
+        .map(|v| v.parse().unwrap())
+        .collect();
+    let bbox: Vec<i32> = header_value(&eps, "%%BoundingBox:")
+        .split(' ')
+        .map(|v| v.parse().unwrap())
+        .collect();
+
+    assert_eq!(&hires[..2], &[0.0, 0.0]);
+    assert!(hires[2] > 0.0 && hires[3] > 0.0);
+    // The integer bounding box encloses the exact one
+    assert_eq!(bbox[2], hires[2].ceil() as i32);
+    assert_eq!(bbox[3], hires[3].ceil() as i32);
+
+    // Glyphs are filled paths, the fraction rule is a rectangle
+    assert!(eps.contains(" curveto ") && eps.contains("fill grestore"));
+    assert!(eps.contains(" rectfill"));
+}
+
+#[test]
+fn test_eps_metadata() {
+    let src = "\\sqrt{x} + ä (y) \\\\";
+    let eps = crate::render_eps(src, true).unwrap();
+    assert!(eps.is_ascii());
+    assert_eq!(
+        crate::get_source_from_metadata(eps.as_bytes()).unwrap(),
+        (src.to_owned(), Syntax::Latex)
+    );
+
+    let options = RenderOptions::default().with_syntax(Syntax::AsciiMath);
+    let eps = crate::render_eps_with("sqrt x + y", true, &options).unwrap();
+    assert_eq!(
+        crate::get_source_from_metadata(eps.as_bytes()).unwrap(),
+        ("sqrt x + y".to_owned(), Syntax::AsciiMath)
+    );
+
+    let eps = crate::render_eps(src, false).unwrap();
+    assert!(matches!(
+        crate::get_source_from_metadata(eps.as_bytes()),
+        Err(crate::Error::MetadataNotFound)
+    ));
+}
@@ -0,0 +1,79 @@
+use crate::Error;
+
+#[test]
+fn test_metadata_not_found() {
+    let result = crate::get_source_from_metadata(b"no image");
+    assert!(matches!(result, Err(Error::MetadataNotFound)));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn test_invalid_font_data() {
+    use crate::backend::{opentype::FontBackend, svg::SvgRenderer};
+
+    let result = FontBackend::<SvgRenderer>::new_from_font_data(b"no font");
+    assert!(matches!(result, Err(Error::FontParse(_))));
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn test_save_png_io_error() {
+    let result = crate::save_png("x", false, "/nonexistent-directory/image.png");
+    assert!(matches!(result, Err(Error::Io(_))));
+}
+
+#[cfg(feature = "tiny-skia")]
+#[test]
+fn test_empty_formula() {
+    use crate::{options::Padding, RenderOptions};
+
+    let options = RenderOptions::default().with_padding(Padding::uniform(0.0));
+    let pixmap = crate::render_string_with("", &options).unwrap();
+    assert_eq!((pixmap.width(), pixmap.height()), (1, 1));
+
+    // Empty numerator and denominator result in a rule of width zero
+    crate::render_string_with("\\frac{}{}", &options).unwrap();
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn test_empty_formula_svg() {
+    crate::render_svg("", false).unwrap();
+}
+
+#[cfg(all(feature = "svg", feature = "embedded-font"))]
+#[test]
+fn test_font_without_math_table() {
+    use crate::backend::{opentype::FontBackend, svg::SvgRenderer};
+    use crate::layout::Node;
+
+    fn has_error<G: crate::common::Glyph>(node: &Node<G>) -> bool {
+        match node {
+            Node::Glyph { color, .. } => *color == crate::common::Color::Error,
+            Node::HBox { children, .. } | Node::VBox { children, .. } => {
+                children.iter().any(|(_, child)| has_error(child))
+            }
+            Node::Group { child, .. } => has_error(child),
+            _ => false,
+        }
+    }
+
+    // A text font, which has neither a MATH table nor math italic letters
+    let data = include_bytes!("fonts/kerning.ttf");
+    let fb = FontBackend::<SvgRenderer>::new_from_font_data(data).unwrap();
+    assert!(fb.is_degraded());
+
+    // The MATH constants are synthesized, and letters fall back to the plain
+    // characters
+    for src in ["x+y=2", "\\frac{a^2}{b_1} + \\mathbf{v}"] {
+        let list = crate::parser::parse(src).unwrap();
+        let node = list.translate(&fb, 36.0, crate::mathlist::Style::Display);
+        assert!(!has_error(&node), "{}", src);
+    }
+
+    // The render is marked as degraded
+    let svg = crate::render_svg_with_font("x+y=2", true, &fb, &Default::default()).unwrap();
+    assert!(svg.contains("rustmath_degraded"));
+
+    assert!(!crate::backend::opentype::FontSet::shared_default().is_degraded());
+}
@@ -0,0 +1,306 @@
+use crate::backend::opentype::{FaceSelector, FontBackend, FontSet, OwnedFontBackend};
+use crate::backend::svg::SvgRenderer;
+use std::sync::Arc;
+
+const NEWCM_REGULAR: &[u8] = include_bytes!("../../data/NewCMMath-Regular.otf");
+const LATIN_MODERN: &[u8] = include_bytes!("../../data/latinmodern-math.otf");
+
+/// Combines several OpenType fonts into a font collection
+fn make_collection(fonts: &[&[u8]]) -> Vec<u8> {
+    let read_u16 =
+        |data: &[u8], offset: usize| u16::from_be_bytes([data[offset], data[offset + 1]]);
+    let read_u32 = |data: &[u8], offset: usize| {
+        u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap())
+    };
+
+    let mut result = Vec::new();
+    result.extend_from_slice(b"ttcf");
+    result.extend_from_slice(&0x00010000u32.to_be_bytes());
+    result.extend_from_slice(&(fonts.len() as u32).to_be_bytes());
+    result.resize(result.len() + 4 * fonts.len(), 0);
+
+    for (idx, font) in fonts.iter().enumerate() {
+        let start = result.len();
+        result[12 + 4 * idx..16 + 4 * idx].copy_from_slice(&(start as u32).to_be_bytes());
+        result.extend_from_slice(font);
+
+        // Table offsets are relative to the start of the file
+        let num_tables = read_u16(font, 4) as usize;
+        for table in 0..num_tables {
+            let offset = 12 + 16 * table + 8;
+            let table_offset = read_u32(font, offset) + start as u32;
+            result[start + offset..start + offset + 4].copy_from_slice(&table_offset.to_be_bytes());
+        }
+
+        result.resize((result.len() + 3) & !3, 0);
+    }
+
+    result
+}
+
+fn render_with<R: crate::backend::opentype::OpenTypeRenderer>(fb: FontBackend<R>) -> R::Image {
+    let list = crate::parser::parse("x^2").unwrap();
+    let node = list.translate(&fb, 36.0, crate::mathlist::Style::Display);
+    crate::render_layout(&fb, node, &Default::default()).unwrap()
+}
+
+#[test]
+fn test_owned_font_data() {
+    let fb = OwnedFontBackend::<SvgRenderer>::new_from_owned_font_data(
+        NEWCM_REGULAR.to_vec(),
+        FaceSelector::default(),
+    )
+    .unwrap();
+    render_with(fb);
+}
+
+#[test]
+fn test_font_from_file() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/latinmodern-math.otf");
+    let fb = OwnedFontBackend::<SvgRenderer>::new_from_file(path).unwrap();
+    render_with(fb);
+
+    let result = OwnedFontBackend::<SvgRenderer>::new_from_file("/nonexistent.otf");
+    assert!(matches!(result, Err(crate::Error::Io(_))));
+}
+
+#[test]
+fn test_font_collection() {
+    let collection = make_collection(&[NEWCM_REGULAR, LATIN_MODERN]);
+
+    for selector in [
+        FaceSelector::Index(1),
+        FaceSelector::PostScriptName("LatinModernMath-Regular"),
+    ] {
+        let fb = FontBackend::<SvgRenderer>::new_from_collection(&collection, selector).unwrap();
+        let from_collection = render_with(fb);
+
+        let fb = FontBackend::<SvgRenderer>::new_from_font_data(LATIN_MODERN).unwrap();
+        let expected = render_with(fb);
+
+        let mut from_collection_svg = String::new();
+        let mut expected_svg = String::new();
+        from_collection
+            .write(&[], &mut from_collection_svg)
+            .unwrap();
+        expected.write(&[], &mut expected_svg).unwrap();
+        assert_eq!(from_collection_svg, expected_svg);
+    }
+
+    let result = FontBackend::<SvgRenderer>::new_from_collection(
+        &collection,
+        FaceSelector::PostScriptName("Unknown"),
+    );
+    assert!(matches!(result, Err(crate::Error::FaceNotFound(_))));
+}
+
+/// Returns the color, the face and the glyph id of the first glyph
+fn first_glyph<R: crate::backend::opentype::OpenTypeRenderer>(
+    node: &crate::layout::Node<crate::backend::opentype::Glyph<R>>,
+) -> Option<(crate::common::Color, u16, u16)> {
+    use crate::layout::Node;
+
+    match node {
+        Node::Glyph { glyph, color } => Some((*color, glyph.face, glyph.id.0)),
+        Node::HBox { children, .. } | Node::VBox { children, .. } => {
+            children.iter().find_map(|(_, child)| first_glyph(child))
+        }
+        Node::Group { child, .. } => first_glyph(child),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_fallback_font() {
+    use crate::common::Color;
+
+    // Latin Modern does not contain '½', but NewCM does
+    let translate = |fb: &FontBackend<SvgRenderer>| {
+        let list = crate::parser::parse("½").unwrap();
+        let node = list.translate(fb, 36.0, crate::mathlist::Style::Display);
+        let (color, face, _) = first_glyph(&node).unwrap();
+        (color, face)
+    };
+
+    let mut fonts = FontSet::new_from_font_data(LATIN_MODERN).unwrap();
+    let fb = FontBackend::<SvgRenderer>::new(fonts.clone());
+    assert_eq!(translate(&fb), (Color::Error, 0));
+
+    fonts
+        .add_fallback_font(NEWCM_REGULAR, FaceSelector::default())
+        .unwrap();
+    let fb = FontBackend::<SvgRenderer>::new(fonts);
+    assert_eq!(translate(&fb), (Color::Normal, 1));
+}
+
+#[test]
+fn test_family_fonts() {
+    use crate::common::{Color, Family};
+
+    let translate = |fb: &FontBackend<SvgRenderer>, src: &str| {
+        let list = crate::parser::parse(src).unwrap();
+        let node = list.translate(fb, 36.0, crate::mathlist::Style::Display);
+        first_glyph(&node).unwrap()
+    };
+    let glyph_id = |data, ch| {
+        let face = ttf_parser::Face::parse(data, 0).unwrap();
+        face.glyph_index(ch).unwrap().0
+    };
+
+    let mut fonts = FontSet::new_from_font_data(NEWCM_REGULAR).unwrap();
+    let fb = FontBackend::<SvgRenderer>::new(fonts.clone());
+
+    // Without dedicated faces, the math alphanumerics are used
+    let bold_x = glyph_id(NEWCM_REGULAR, '𝐱');
+    assert_eq!(translate(&fb, "\\mathbf{x}"), (Color::Normal, 0, bold_x));
+    let italic_x = glyph_id(NEWCM_REGULAR, '𝑥');
+    assert_eq!(translate(&fb, "x"), (Color::Normal, 0, italic_x));
+
+    fonts
+        .set_family_font(Family::Roman, LATIN_MODERN, FaceSelector::default())
+        .unwrap();
+    let fb = FontBackend::<SvgRenderer>::new(fonts);
+    let roman_x = glyph_id(LATIN_MODERN, 'x');
+    assert_eq!(translate(&fb, "\\mathrm{x}"), (Color::Normal, 1, roman_x));
+    assert_eq!(translate(&fb, "x"), (Color::Normal, 0, italic_x));
+
+    // Characters missing in the dedicated face are taken from the math font
+    let half = glyph_id(NEWCM_REGULAR, '½');
+    assert_eq!(translate(&fb, "\\mathrm{½}"), (Color::Normal, 0, half));
+}
+
+#[test]
+fn test_glyph_cache() {
+    let fb = FontBackend::<SvgRenderer>::new_from_font_data(NEWCM_REGULAR).unwrap();
+    let render = || crate::render_svg_with_font("x^2 + x^2", false, &fb, &Default::default());
+
+    let first = render().unwrap();
+    let stats = fb.cache_stats();
+    assert!(stats.hits > 0);
+    assert!(stats.misses > 0);
+
+    // The second rendering is served from the cache
+    let second = render().unwrap();
+    assert_eq!(first, second);
+    assert_eq!(fb.cache_stats().misses, stats.misses);
+
+    fb.set_cache_capacity(1);
+    assert_eq!(fb.cache_stats().len, 1);
+    assert!(fb.cache_stats().evictions > 0);
+
+    fb.set_cache_capacity(0);
+    assert_eq!(fb.cache_stats().len, 0);
+    assert_eq!(render().unwrap(), first);
+    assert_eq!(fb.cache_stats().len, 0);
+}
+
+#[test]
+fn test_shared_font_set() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<FontSet>();
+    assert_send_sync::<FontBackend<SvgRenderer>>();
+    #[cfg(feature = "tiny-skia")]
+    assert_send_sync::<FontBackend<crate::backend::raster::TinySkiaRenderer>>();
+
+    const SRC: &str = "\\frac{x^2}{\\left(y + 1\\right)}";
+
+    let fonts = Arc::new(FontSet::new_from_font_data(NEWCM_REGULAR).unwrap());
+    let fb = FontBackend::<SvgRenderer>::new(fonts.clone());
+    let expected = crate::render_svg_with_font(SRC, false, &fb, &Default::default()).unwrap();
+
+    // One backend, used by several threads at once
+    std::thread::scope(|scope| {
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                scope.spawn(|| {
+                    crate::render_svg_with_font(SRC, false, &fb, &Default::default()).unwrap()
+                })
+            })
+            .collect();
+
+        for thread in threads {
+            assert_eq!(thread.join().unwrap(), expected);
+        }
+    });
+
+    // The same font set serves a different output format
+    #[cfg(feature = "tiny-skia")]
+    {
+        let raster = FontBackend::<crate::backend::raster::TinySkiaRenderer>::new(fonts.clone());
+        crate::render_string_with_font(SRC, &raster, &Default::default()).unwrap();
+        assert!(Arc::ptr_eq(raster.font_set(), fb.font_set()));
+    }
+}
+
+#[test]
+fn test_font_inspection() {
+    use crate::common::Family;
+
+    let fonts = FontSet::new_from_font_data(LATIN_MODERN).unwrap();
+
+    let constants = fonts.math_constants();
+    assert_eq!(constants.script_percent_scale_down, 70.0);
+    assert!(constants.axis_height > 0.0);
+    assert!(constants.radical_rule_thickness > 0.0);
+
+    // Parentheses grow vertically, using variants and then an assembly
+    let paren = fonts.char_variants('(').unwrap();
+    let vertical = paren.vertical.unwrap();
+    assert_eq!(vertical.variants[0].glyph, paren.glyph);
+    assert!(vertical
+        .variants
+        .windows(2)
+        .all(|pair| pair[0].advance <= pair[1].advance));
+    assert!(vertical
+        .assembly
+        .unwrap()
+        .parts
+        .iter()
+        .any(|part| part.extender));
+    assert!(paren.horizontal.is_none());
+    assert!(fonts.char_variants('x').unwrap().vertical.is_none());
+
+    let chars = fonts.supported_chars(Family::Roman);
+    assert!(chars.binary_search(&'∑').is_ok());
+    assert!(chars.binary_search(&'½').is_err());
+
+    let coverage = fonts.command_coverage();
+    let find = |command| coverage.iter().find(|c| c.command == command).unwrap();
+    assert!(find("infty").is_supported());
+    assert!(find("sum").is_supported());
+
+    // Latin Modern does not contain all commands, but NewCM provides them
+    let missing = coverage.iter().filter(|c| !c.is_supported()).count();
+    assert!(missing > 0);
+    let mut fonts = fonts;
+    fonts
+        .add_fallback_font(NEWCM_REGULAR, FaceSelector::default())
+        .unwrap();
+    let fallback_missing = fonts
+        .command_coverage()
+        .iter()
+        .filter(|c| !c.is_supported())
+        .count();
+    assert!(fallback_missing < missing);
+}
+
+#[test]
+fn test_font_embedding() {
+    let fonts = FontSet::new_from_font_data(LATIN_MODERN).unwrap();
+    let face = ttf_parser::Face::parse(LATIN_MODERN, 0).unwrap();
+    let x = face.glyph_index('x').unwrap();
+    let italic_x = face.glyph_index('𝑥').unwrap();
+    assert_eq!(fonts.face_data(0), Some(LATIN_MODERN));
+    assert_eq!(fonts.glyph_char(0, x), Some('x'));
+    assert_eq!(fonts.glyph_char(0, italic_x), Some('𝑥'));
+
+    // The first size variant of the parenthesis has no character of its own
+    let paren = fonts.char_variants('(').unwrap();
+    assert_eq!(fonts.glyph_char(0, paren.glyph), Some('('));
+    let large_paren = paren.vertical.unwrap().variants[1].glyph;
+    assert_eq!(fonts.glyph_char(0, large_paren), None);
+
+    // Unknown faces
+    assert_eq!(fonts.face_data(1), None);
+    assert_eq!(fonts.glyph_char(1, x), None);
+}
@@ -174,8 +174,7 @@ impl<'a> MathTableRow<'a> {
             )
         };
 
-        let parse_classify =
-            nom::combinator::map(parse_command(), |cmd| MathClass::from_command(cmd));
+        let parse_classify = nom::combinator::map(parse_command(), MathClass::from_command);
 
         let parse_main_command = nom::sequence::delimited(
             nom::bytes::complete::tag("{"),
@@ -298,7 +297,7 @@ mod data {
         pub fn print_commands(&self, out: &mut impl core::fmt::Write) -> core::fmt::Result {
             writeln!(
                 out,
-                "pub static CHAR_COMMANDS: [(&'static str, char); {}] = [",
+                "pub static CHAR_COMMANDS: [(&str, char); {}] = [",
                 self.commands.len()
             )?;
             for (cmd, ch) in &self.commands {
@@ -1,20 +1,53 @@
-fn test_png_image(png: &[u8]) {
-    let source = crate::get_source_from_png_metadata(png).unwrap();
-    let encoded = crate::encode_png(&source, true).unwrap();
+use crate::backend::opentype::{EmbeddedFont, FontBackend};
+
+fn test_png_image(png: &[u8], font: EmbeddedFont) {
+    let (source, syntax) = crate::get_source_from_png_metadata(png).unwrap();
+    let fb = FontBackend::new_embedded(font);
+    let options = crate::RenderOptions::default().with_syntax(syntax);
+    let encoded = crate::encode_png_with_font(&source, true, &fb, &options).unwrap();
 
     assert_eq!(png, &encoded);
 }
 
-#[test]
-fn test_images() {
-    test_png_image(include_bytes!("../../../ci/test-images/basel.png"));
-    test_png_image(include_bytes!("../../../ci/test-images/cauchy.png"));
-    test_png_image(include_bytes!("../../../ci/test-images/euler.png"));
-    test_png_image(include_bytes!("../../../ci/test-images/euler-lagrange.png"));
-    test_png_image(include_bytes!("../../../ci/test-images/gamma.png"));
-    test_png_image(include_bytes!(
-        "../../../ci/test-images/minimal_surface.png"
-    ));
-    test_png_image(include_bytes!("../../../ci/test-images/parse_error.png"));
-    test_png_image(include_bytes!("../../../ci/test-images/stokes.png"));
+macro_rules! test_images {
+    ($name:ident, $font:expr, $dir:literal) => {
+        #[test]
+        fn $name() {
+            macro_rules! test_image {
+                ($file:literal) => {
+                    test_png_image(
+                        include_bytes!(concat!("../../../ci/test-images/", $dir, $file)),
+                        $font,
+                    )
+                };
+            }
+
+            test_image!("basel.png");
+            test_image!("cauchy.png");
+            test_image!("euler.png");
+            test_image!("euler-lagrange.png");
+            test_image!("gamma.png");
+            test_image!("large_integral.png");
+            test_image!("minimal_surface.png");
+            test_image!("parse_error.png");
+            test_image!("stokes.png");
+        }
+    };
 }
+
+#[cfg(feature = "font-newcm-regular")]
+test_images!(test_images_newcm_regular, EmbeddedFont::NewCMRegular, "");
+
+#[cfg(feature = "font-newcm-book")]
+test_images!(
+    test_images_newcm_book,
+    EmbeddedFont::NewCMBook,
+    "newcm-book/"
+);
+
+#[cfg(feature = "font-latinmodern")]
+test_images!(
+    test_images_latinmodern,
+    EmbeddedFont::LatinModern,
+    "latinmodern/"
+);
@@ -0,0 +1,138 @@
+use crate::backend::{opentype::FontBackend, svg::SvgRenderer};
+use crate::common::{Family, FontBackend as _, FontStyle};
+use crate::layout::Node;
+use crate::parser::Syntax;
+
+const NEWCM_REGULAR: &[u8] = include_bytes!("../../data/NewCMMath-Regular.otf");
+/// A text font without a MATH table, but with GPOS kerning for `AV` and `To`.
+/// Written by `ci/make-test-font.py`
+const KERNING_FONT: &[u8] = include_bytes!("fonts/kerning.ttf");
+
+fn translate_with(font: &[u8], src: &str) -> Node<crate::backend::opentype::Glyph<SvgRenderer>> {
+    let fb = FontBackend::<SvgRenderer>::new_from_font_data(font).unwrap();
+    let list = crate::parser::parse(src).unwrap();
+    list.translate(&fb, 36.0, crate::mathlist::Style::Display)
+}
+
+fn translate(src: &str) -> Node<crate::backend::opentype::Glyph<SvgRenderer>> {
+    translate_with(NEWCM_REGULAR, src)
+}
+
+/// The translation of the first atom, without its group
+fn first_atom<G: crate::common::Glyph>(node: &Node<G>) -> &Node<G> {
+    match node {
+        Node::HBox { children, .. } => match &children[0].1 {
+            Node::Group { child, .. } => child.as_ref(),
+            _ => panic!("expected an atom"),
+        },
+        _ => panic!("expected a list"),
+    }
+}
+
+/// Returns true if the first atom ends with an italic correction
+fn has_italic_correction(src: &str) -> bool {
+    match first_atom(&translate(src)) {
+        Node::HBox { children, .. } => matches!(children.last(), Some((_, Node::Glue(_)))),
+        _ => false,
+    }
+}
+
+/// Sum of the kerns between the atoms of the list and its sublists
+fn kerning(src: &str) -> f32 {
+    fn sum<G: crate::common::Glyph>(node: &Node<G>) -> f32 {
+        match node {
+            Node::Glue(glue) => *glue,
+            Node::HBox { children, .. } => children.iter().map(|(_, child)| sum(child)).sum(),
+            Node::Group { child, .. } => sum(child),
+            _ => 0.0,
+        }
+    }
+    sum(&translate_with(KERNING_FONT, src))
+}
+
+/// Horizontal shifts of the scripts of the first atom, from bottom to top
+fn script_shifts(src: &str) -> Vec<f32> {
+    let node = translate(src);
+    let Node::HBox { children, .. } = first_atom(&node) else {
+        panic!("expected an atom with scripts");
+    };
+    match children.last() {
+        Some((_, Node::VBox { children, .. })) => children
+            .iter()
+            .filter(|(_, child)| !matches!(child, Node::Glue(_)))
+            .map(|(hshift, _)| *hshift)
+            .collect(),
+        _ => panic!("expected scripts"),
+    }
+}
+
+#[test]
+fn test_italic_correction() {
+    // Followed by upright material or at the end of the list
+    assert!(has_italic_correction("f)"));
+    assert!(has_italic_correction("f"));
+
+    // Followed by another italic symbol
+    assert!(!has_italic_correction("ff"));
+
+    // With scripts, the italic correction shifts the superscript instead
+    assert!(!has_italic_correction("f^2"));
+}
+
+#[test]
+fn test_operator_scripts() {
+    // The bundled fonts have no corner kerning for large operators. The
+    // subscript is shifted left by the italic correction instead
+    let shifts = script_shifts(r"\int_a^b");
+    assert_eq!(shifts.len(), 2);
+    assert!(shifts[1] - shifts[0] > 1.0);
+
+    // Without a subscript, the superscript follows the advance
+    assert_eq!(script_shifts(r"\int^b"), [0.0]);
+}
+
+#[test]
+fn test_pair_kerning() {
+    // Adjacent Ord symbols are kerned
+    assert!(kerning(r"\mathrm{AV}") < 0.0);
+    assert!(kerning(r"\mathrm{To}") < 0.0);
+    assert_eq!(kerning(r"\mathrm{AB}"), 0.0);
+
+    // Scripts separate the glyphs
+    assert_eq!(kerning(r"\mathrm{A}^2\mathrm{V}"), 0.0);
+}
+
+#[test]
+fn test_radical() {
+    let fb = FontBackend::<SvgRenderer>::new_from_font_data(NEWCM_REGULAR).unwrap();
+    let src = "<msqrt><mfrac><mi>a</mi><mi>b</mi></mfrac></msqrt>";
+    let list = crate::parser::parse_with_syntax(src, Syntax::MathMl).unwrap();
+    let node = list.translate(&fb, 36.0, crate::mathlist::Style::Display);
+    let Node::HBox { children, .. } = first_atom(&node) else {
+        panic!("expected a radical");
+    };
+    let [(sign_shift, sign), (
+        _,
+        Node::VBox {
+            children: parts, ..
+        },
+    )] = &children[..]
+    else {
+        panic!("expected the root sign and the radicand");
+    };
+    let [(_, radicand), (_, Node::Glue(gap)), (_, Node::Rule { height: rule, .. }), _] = &parts[..]
+    else {
+        panic!("expected the radicand below the bar");
+    };
+
+    // The sign is enlarged to cover the fraction, its top meets the top of
+    // the bar
+    let params =
+        fb.get_font(Family::Italic)
+            .calculate_radical_params(36.0, FontStyle::Display, false);
+    assert!(*gap >= params.vertical_gap);
+    assert_eq!(*rule, params.rule_thickness);
+    let top = radicand.height(false) + gap + rule;
+    assert!((sign_shift + sign.height(false) - top).abs() < 1e-3);
+    assert!(sign_shift - sign.depth() <= -radicand.depth() + 1e-3);
+}
@@ -0,0 +1,129 @@
+/// The MathML between the outer `<mrow>` and the annotation
+fn mathml_body(src: &str) -> String {
+    let mathml = crate::render_mathml(src).unwrap();
+    let start = mathml.find("<semantics><mrow>").unwrap() + "<semantics><mrow>".len();
+    let end = mathml.find("</mrow><annotation").unwrap();
+    mathml[start..end].to_owned()
+}
+
+#[test]
+fn test_mathml_tokens() {
+    assert_eq!(
+        mathml_body("x+12.5=\\operatorname{sin} y"),
+        "<mi>𝑥</mi><mo>+</mo><mn>12.5</mn><mo>=</mo><mi>sin</mi><mi>𝑦</mi>"
+    );
+    assert_eq!(
+        mathml_body("\\mathrm{d}x"),
+        "<mi mathvariant=\"normal\">d</mi><mi>𝑥</mi>"
+    );
+}
+
+#[test]
+fn test_mathml_structure() {
+    assert_eq!(
+        mathml_body("\\frac{a+1}{2}"),
+        "<mfrac><mrow><mi>𝑎</mi><mo>+</mo><mn>1</mn></mrow><mn>2</mn></mfrac>"
+    );
+    assert_eq!(
+        mathml_body("x_i^{10}"),
+        "<msubsup><mi>𝑥</mi><mi>𝑖</mi><mn>10</mn></msubsup>"
+    );
+    assert_eq!(
+        mathml_body("\\sum_{k=1}^n k"),
+        "<munderover><mo>∑</mo><mrow><mi>𝑘</mi><mo>=</mo><mn>1</mn></mrow><mi>𝑛</mi></munderover><mi>𝑘</mi>"
+    );
+    assert_eq!(
+        mathml_body("\\left(x\\right."),
+        "<mrow><mo fence=\"true\" form=\"prefix\">(</mo><mi>𝑥</mi></mrow>"
+    );
+}
+
+#[test]
+fn test_mathml_annotation() {
+    let mathml = crate::render_mathml("a<b").unwrap();
+    assert!(mathml.starts_with("<math xmlns=\"http://www.w3.org/1998/Math/MathML\""));
+    assert!(mathml.contains("<mo>&lt;</mo>"));
+    assert!(mathml.ends_with(
+        "<annotation encoding=\"application/x-tex\">a&lt;b</annotation></semantics></math>"
+    ));
+}
+
+#[test]
+fn test_mathml_errors() {
+    assert_eq!(
+        mathml_body("\\unknown"),
+        "<merror><mtext>\\unknown</mtext></merror>"
+    );
+}
+
+/// Parses MathML and writes it again, without the outer `<mrow>`
+fn parse_mathml(src: &str) -> String {
+    let list = crate::parser::mathml::parse::<crate::common::NoGlyph>(src).unwrap();
+    let mut mathml = String::new();
+    crate::mathlist::mathml::write(&list, None, &mut mathml).unwrap();
+
+    let start = mathml.find("<mrow>").unwrap() + "<mrow>".len();
+    let end = mathml.rfind("</mrow>").unwrap();
+    mathml[start..end].to_owned()
+}
+
+#[test]
+fn test_parse_mathml_round_trip() {
+    for src in [
+        "x+12.5=\\operatorname{sin} y",
+        "\\frac{a+1}{2}",
+        "x_i^{10}",
+        "\\sum_{k=1}^n k",
+        "\\left(x\\right.",
+        "\\left[\\frac{1}{2}\\right]",
+    ] {
+        let mathml = crate::render_mathml(src).unwrap();
+        assert_eq!(parse_mathml(&mathml), mathml_body(src), "{}", src);
+    }
+}
+
+#[test]
+fn test_parse_mathml_elements() {
+    let src = r#"<?xml version="1.0"?>
+        <!-- comment -->
+        <m:math xmlns:m="http://www.w3.org/1998/Math/MathML">
+          <m:mi>f</m:mi><m:mo>&#x2061;</m:mo>
+          <m:mfenced><m:mi>a</m:mi><m:mi mathvariant='bold'>b</m:mi></m:mfenced>
+          <m:mo>&lt;</m:mo>
+          <m:msqrt><m:mn>2</m:mn></m:msqrt>
+          <m:munder><m:mo>lim</m:mo><m:mi>n</m:mi></m:munder>
+        </m:math>"#;
+    assert_eq!(
+        parse_mathml(src),
+        "<mi>𝑓</mi>\
+         <mrow><mo fence=\"true\" form=\"prefix\">(</mo><mi>𝑎</mi><mo>,</mo><mi>𝐛</mi>\
+         <mo fence=\"true\" form=\"postfix\">)</mo></mrow>\
+         <mo>&lt;</mo><msqrt><mn>2</mn></msqrt><munder><mi>lim</mi><mi>𝑛</mi></munder>"
+    );
+}
+
+#[test]
+fn test_parse_mathml_table() {
+    let src = "<mtable><mtr><mtd><mi>a</mi></mtd><mtd><mn>1</mn><mo>+</mo><mi>b</mi></mtd></mtr>\
+               <mtr><mtd></mtd></mtr><mn>2</mn></mtable>";
+    assert_eq!(
+        parse_mathml(src),
+        "<mtable><mtr><mtd><mi>𝑎</mi></mtd><mtd><mrow><mn>1</mn><mo>+</mo><mi>𝑏</mi></mrow></mtd></mtr>\
+         <mtr><mtd><mrow></mrow></mtd></mtr><mtr><mtd><mn>2</mn></mtd></mtr></mtable>"
+    );
+}
+
+#[test]
+fn test_parse_mathml_unsupported() {
+    assert_eq!(
+        parse_mathml("<math><mlabeledtr><mtd><mn>1</mn></mtd></mlabeledtr></math>"),
+        "<merror><mtext>mlabeledtr</mtext></merror>"
+    );
+    assert_eq!(
+        parse_mathml("<mfrac><mn>1</mn></mfrac>"),
+        "<merror><mtext>mfrac</mtext></merror>"
+    );
+
+    let result = crate::parser::mathml::parse::<crate::common::NoGlyph>("<math><mi>x</mo></math>");
+    assert!(matches!(result, Err(crate::Error::Parse { position: 11 })));
+}
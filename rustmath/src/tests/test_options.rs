@@ -0,0 +1,45 @@
+use crate::{options::Rgba, RenderOptions};
+
+const SRC: &str = "\\frac{a}{b}";
+
+#[test]
+fn test_dpi() {
+    let default = crate::render_string(SRC).unwrap();
+    let options = RenderOptions::default().with_dpi(192.0);
+    let scaled = crate::render_string_with(SRC, &options).unwrap();
+
+    assert!(scaled.width().abs_diff(2 * default.width()) <= 1);
+    assert!(scaled.height().abs_diff(2 * default.height()) <= 1);
+}
+
+#[test]
+fn test_crop_to_ink() {
+    let options = RenderOptions::default().with_padding(crate::options::Padding::uniform(0.0));
+    let uncropped = crate::render_string_with(SRC, &options).unwrap();
+
+    let options = options.with_crop_to_ink(true);
+    let cropped = crate::render_string_with(SRC, &options).unwrap();
+
+    assert!(cropped.width() <= uncropped.width());
+    assert!(cropped.height() <= uncropped.height());
+
+    // Without padding, the ink touches all four borders
+    let (width, height) = (cropped.width(), cropped.height());
+    let has_ink = |x, y| cropped.pixel(x, y).unwrap().alpha() > 0;
+    assert!((0..height).any(|y| has_ink(0, y)));
+    assert!((0..height).any(|y| has_ink(width - 1, y)));
+    assert!((0..width).any(|x| has_ink(x, 0)));
+    assert!((0..width).any(|x| has_ink(x, height - 1)));
+}
+
+#[test]
+fn test_background() {
+    let options = RenderOptions::default().with_background(Some(Rgba::WHITE));
+    let pixmap = crate::render_string_with(SRC, &options).unwrap();
+
+    let corner = pixmap.pixel(0, 0).unwrap().demultiply();
+    assert_eq!(
+        (corner.red(), corner.green(), corner.blue(), corner.alpha()),
+        (255, 255, 255, 255)
+    );
+}
@@ -0,0 +1,81 @@
+use crate::{options::Rgba, parser::Syntax, RenderOptions};
+
+/// Returns the number following `key` in the document
+fn find_number(pdf: &str, key: &str) -> usize {
+    let start = pdf.find(key).unwrap() + key.len();
+    let digits: String = pdf[start..]
+        .trim_start()
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().unwrap()
+}
+
+#[test]
+fn test_pdf_structure() {
+    let pdf = crate::render_pdf("\\frac{a}{b}", false).unwrap();
+    let text = String::from_utf8_lossy(&pdf);
+
+    assert!(text.starts_with("%PDF-1.4"));
+    assert!(text.ends_with("%%EOF\n"));
+
+    // The cross reference table points to the objects
+    let xref = find_number(&text, "startxref");
+    assert!(pdf[xref..].starts_with(b"xref"));
+    let object = find_number(&text[xref..], "0000000000 65535 f \n");
+    assert!(pdf[object..].starts_with(b"1 0 obj"));
+
+    // The stream length is correct
+    let length = find_number(&text, "/Length");
+    let start = text.find("stream\n").unwrap() + "stream\n".len();
+    assert!(text[start + length..].starts_with("endstream"));
+
+    // Glyphs are filled paths, the fraction rule is a rectangle
+    assert!(text.contains(" cm ") && text.contains(" f Q"));
+    assert!(text.contains(" re f"));
+}
+
+#[test]
+fn test_pdf_size_and_colors() {
+    let options = RenderOptions::default()
+        .with_background(Some(Rgba::WHITE))
+        .with_foreground(Rgba::new(255, 0, 0, 128));
+    let pdf = crate::render_pdf_with("x", false, &options).unwrap();
+    let text = String::from_utf8_lossy(&pdf);
+
+    // The page has the size of the layout
+    let fb = crate::backend::opentype::FontBackend::<crate::backend::pdf::PdfRenderer>::default();
+    let list = crate::parser::parse("x").unwrap();
+    let node = list.translate(&fb, options.font_size, crate::mathlist::Style::Display);
+    let width = node.advance(false) + options.padding.left + options.padding.right;
+    let media_box = text.split("/MediaBox [0 0 ").nth(1).unwrap();
+    let pdf_width: f32 = media_box.split(' ').next().unwrap().parse().unwrap();
+    assert!((pdf_width - width).abs() < 1e-2);
+
+    assert!(text.contains("1 1 1 rg"));
+    assert!(text.contains("1 0 0 rg"));
+    assert!(text.contains("/ca 0.502"));
+}
+
+#[test]
+fn test_pdf_metadata() {
+    let src = "\\sqrt{x} + ä (y)";
+    let pdf = crate::render_pdf(src, true).unwrap();
+    assert_eq!(
+        crate::get_source_from_metadata(&pdf).unwrap(),
+        (src.to_owned(), Syntax::Latex)
+    );
+
+    let options = RenderOptions::default().with_syntax(Syntax::Typst);
+    let pdf = crate::render_pdf_with("sqrt(x) + y", true, &options).unwrap();
+    assert_eq!(
+        crate::get_source_from_metadata(&pdf).unwrap(),
+        ("sqrt(x) + y".to_owned(), Syntax::Typst)
+    );
+
+    let pdf = crate::render_pdf(src, false).unwrap();
+    assert!(matches!(
+        crate::get_source_from_metadata(&pdf),
+        Err(crate::Error::MetadataNotFound)
+    ));
+}
@@ -0,0 +1,162 @@
+use crate::RenderOptions;
+
+#[test]
+fn test_svg_glyph_deduplication() {
+    let svg = crate::render_svg("x + x + x^x", false).unwrap();
+
+    // Two distinct sizes of x and the plus sign
+    assert_eq!(svg.matches("<path ").count(), 3);
+    assert_eq!(svg.matches("<use ").count(), 6);
+
+    // Every reference points to a definition. SVG 1.1 viewers only know the
+    // XLink attribute
+    assert!(svg.contains(" xmlns:xlink=\"http://www.w3.org/1999/xlink\""));
+    for reference in svg.split(" xlink:href=\"#").skip(1) {
+        let id = reference.split('"').next().unwrap();
+        assert!(svg.contains(&format!("<path id=\"{}\"", id)));
+    }
+}
+
+#[test]
+fn test_svg_ids() {
+    let id = |svg: &str| svg.split("<path id=\"").nth(1).unwrap()[..17].to_owned();
+
+    // The ids must not depend on the Rust version or platform
+    let svg = crate::render_svg("x", false).unwrap();
+    assert_eq!(id(&svg), "g49c94e8ab1019e31");
+
+    // Different formulas get different ids, even if they look the same
+    assert_ne!(id(&crate::render_svg("y", false).unwrap()), id(&svg));
+    assert_ne!(id(&crate::render_svg("{x}", true).unwrap()), id(&svg));
+    let options = RenderOptions::default().with_svg_accessibility(true);
+    let labeled = crate::render_svg_with("x", false, &options).unwrap();
+    assert!(labeled.contains(&format!(
+        "aria-labelledby=\"{0}-title {0}-desc\"",
+        id(&labeled)
+    )));
+    assert_ne!(id(&labeled), id(&svg));
+
+    // The same formula can be embedded twice with a prefix of the caller
+    let options = options.with_svg_id_prefix(Some("eq2".to_owned()));
+    let svg = crate::render_svg_with("x", false, &options).unwrap();
+    assert!(svg.contains(" aria-labelledby=\"eq2-title eq2-desc\""));
+    assert!(svg.contains("<path id=\"eq2-0\""));
+    assert!(svg.contains(" xlink:href=\"#eq2-0\""));
+}
+
+#[test]
+fn test_svg_precision() {
+    let decimals = |svg: &str| {
+        svg.split("d=\"")
+            .skip(1)
+            .flat_map(|data| data.split('"').next().unwrap().split(' '))
+            .filter_map(|token| token.split_once('.'))
+            .map(|(_, decimals)| decimals.len())
+            .max()
+            .unwrap()
+    };
+
+    let options = RenderOptions::default().with_svg_precision(Some(1));
+    let rounded = crate::render_svg_with("\\frac{a}{b}", false, &options).unwrap();
+    assert_eq!(decimals(&rounded), 1);
+
+    let options = RenderOptions::default().with_svg_precision(None);
+    let exact = crate::render_svg_with("\\frac{a}{b}", false, &options).unwrap();
+    assert!(decimals(&exact) > 2);
+    assert!(rounded.len() < exact.len());
+}
+
+#[test]
+fn test_svg_accessibility() {
+    let plain = crate::render_svg("x^2", false).unwrap();
+    assert!(!plain.contains("role="));
+    assert!(!plain.contains("<g data-atom"));
+
+    let options = RenderOptions::default().with_svg_accessibility(true);
+    let svg = crate::render_svg_with("\\frac{a}{b} + x^2 < \\mitalpha", true, &options).unwrap();
+    assert!(svg.contains(" role=\"img\" aria-labelledby=\""));
+    assert!(svg.contains(">\\frac{a}{b} + x^2 &#60; \\mitalpha</title>"));
+    assert!(svg.contains(">a over b plus x squared is less than alpha</desc>"));
+    for atom_type in ["inner", "bin", "ord", "rel"] {
+        assert!(svg.contains(&format!("<g data-atom=\"{}\">", atom_type)));
+    }
+    // Numerator and denominator are nested within the fraction
+    assert_eq!(svg.matches("<g data-atom").count(), 7);
+    assert_eq!(svg.matches("<g ").count(), svg.matches("</g>").count());
+
+    // The labels do not hide the source
+    let (source, _) = crate::get_source_from_metadata(svg.as_bytes()).unwrap();
+    assert_eq!(source, "\\frac{a}{b} + x^2 < \\mitalpha");
+}
+
+#[test]
+fn test_spoken_description() {
+    let describe_with = |src: &str, syntax| {
+        let list = crate::parser::parse_with_syntax::<
+            crate::backend::opentype::Glyph<crate::backend::svg::SvgRenderer>,
+        >(src, syntax)
+        .unwrap();
+        crate::mathlist::speech::describe(&list)
+    };
+    let describe = |src: &str| describe_with(src, crate::parser::Syntax::Latex);
+
+    assert_eq!(describe("x_1 + 12.5"), "x sub 1 plus 12.5");
+    assert_eq!(describe("e^{i+1}"), "e to the power of i plus 1 end power");
+    assert_eq!(
+        describe("\\frac{a+b}{2}"),
+        "fraction a plus b over 2 end fraction"
+    );
+    assert_eq!(
+        describe("\\left(\\mupGamma\\right)"),
+        "open paren capital gamma close paren"
+    );
+
+    let mathml = |src: &str| describe_with(src, crate::parser::Syntax::MathMl);
+    assert_eq!(mathml("<msqrt><mi>x</mi></msqrt>"), "square root of x");
+    assert_eq!(
+        mathml("<msqrt><mi>x</mi><mo>+</mo><mn>1</mn></msqrt>"),
+        "square root of x plus 1 end root"
+    );
+}
+
+#[test]
+fn test_svg_text() {
+    let src = "\\frac{x^2}{\\left(y\\right)} + z";
+    let paths = crate::render_svg(src, false).unwrap();
+
+    let options = RenderOptions::default().with_svg_text(true);
+    let svg = crate::render_svg_with(src, true, &options).unwrap();
+    assert!(svg.contains("@font-face"));
+    assert!(svg.contains("src: url(\"data:font/otf;base64,"));
+
+    // The math italic letters are written as such. The superscript is the
+    // script variant of the digit, which has no character of its own
+    let text_of = |svg: &str| -> String {
+        svg.split("\">")
+            .filter_map(|part| part.split_once("</tspan>"))
+            .map(|(text, _)| text)
+            .collect()
+    };
+    let text = text_of(&svg);
+    for ch in ['𝑥', '𝑦', '+', '𝑧', '(', ')'] {
+        assert!(text.contains(ch), "{} is missing in {:?}", ch, text);
+    }
+    assert!(!text.contains('2'));
+    assert_eq!(svg.matches("<use ").count(), 1);
+
+    // Without the text, the images are identical
+    assert_eq!(
+        svg.matches("<rect ").count(),
+        paths.matches("<rect ").count()
+    );
+    assert_eq!(svg.matches("<tspan ").count(), 3);
+    let (source, _) = crate::get_source_from_metadata(svg.as_bytes()).unwrap();
+    assert_eq!(source, src);
+
+    // Enlarged delimiters have no character of their own, so they are drawn
+    // as outlines
+    let svg = crate::render_svg_with("\\left(\\frac{x}{y}\\right)", true, &options).unwrap();
+    assert_eq!(svg.matches("<use ").count(), 2);
+    assert!(!text_of(&svg).contains('('));
+    assert!(text_of(&svg).contains('𝑦'));
+}
@@ -0,0 +1,58 @@
+use crate::parser::Syntax;
+
+fn render(src: &str) -> String {
+    crate::render_text(src).unwrap()
+}
+
+#[test]
+fn test_single_line() {
+    assert_eq!(render("a+b+c=d,e"), "a + b + c = d, e");
+    assert_eq!(render(r"\mathbf{x}y"), "𝐱y");
+}
+
+#[test]
+fn test_fraction() {
+    assert_eq!(render(r"\frac{a}{b}"), "a\n─\nb");
+    assert_eq!(render(r"\frac{x+1}{2}"), "x + 1\n─────\n  2");
+    assert_eq!(render(r"1+\frac{1}{2}"), "    1\n1 + ─\n    2");
+}
+
+#[test]
+fn test_scripts() {
+    assert_eq!(render("x^2"), " 2\nx");
+    assert_eq!(render("x_i^2"), " 2\nx\n i");
+    assert_eq!(render("e^{x^2}"), "  2\n x\ne");
+}
+
+#[test]
+fn test_delimiters() {
+    assert_eq!(render(r"\left(\frac{a}{b}\right)"), "⎛a⎞\n⎜─⎟\n⎝b⎠");
+    assert_eq!(render(r"\left(\frac{a}{b}\right)^2"), "⎛a⎞2\n⎜─⎟\n⎝b⎠");
+    assert_eq!(render(r"\left[x\right]"), "[x]");
+    assert_eq!(render(r"\int \frac{1}{x}"), "⌠ 1\n⎮ ─\n⌡ x");
+    assert_eq!(render(r"\sum \frac{1}{i}"), "⎲ 1\n⎳ ─\n  i");
+}
+
+#[test]
+fn test_syntax() {
+    let asciimath = crate::render_text_with("(a)/(b)", Syntax::AsciiMath).unwrap();
+    assert_eq!(asciimath, render(r"\frac{a}{b}"));
+}
+
+#[test]
+fn test_table() {
+    let mathml = |src| crate::render_text_with(src, Syntax::MathMl).unwrap();
+    let src = "<mrow><mo fence=\"true\">(</mo><mtable>\
+               <mtr><mtd><mi>a</mi></mtd><mtd><mn>10</mn></mtd></mtr>\
+               <mtr><mtd><mn>123</mn></mtd><mtd><mi>b</mi></mtd></mtr>\
+               </mtable><mo fence=\"true\">)</mo></mrow>";
+    assert_eq!(mathml(src), "⎛ a   10 ⎞\n⎜        ⎟\n⎝123   b ⎠");
+}
+
+#[test]
+fn test_radical() {
+    let mathml = |src| crate::render_text_with(src, Syntax::MathMl).unwrap();
+    assert_eq!(mathml("<msqrt><mi>x</mi></msqrt>"), "│─\n⎷x");
+    let src = "<msqrt><mfrac><mi>a</mi><mi>b</mi></mfrac></msqrt>";
+    assert_eq!(mathml(src), "│─\n│a\n│─\n⎷b");
+}
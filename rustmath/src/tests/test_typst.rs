@@ -0,0 +1,91 @@
+use crate::parser::{typst, Syntax};
+
+/// The MathML of the formula, without the annotation
+fn mathml_body(src: &str, syntax: Syntax) -> String {
+    let mathml = crate::render_mathml_with(src, syntax).unwrap();
+    let end = mathml.find("</mrow>").unwrap();
+    mathml[..end].to_owned()
+}
+
+#[test]
+fn test_typst_like_latex() {
+    for (typst, latex) in [
+        (
+            "sum_(i=0)^n i = frac(n(n+1), 2)",
+            "\\sum_{i=0}^n i = \\frac{n\\left(n+1\\right)}{2}",
+        ),
+        ("(a+b)/2 - x^(-1)", "\\frac{a+b}{2}-x^{-1}"),
+        (
+            "alpha arrow.r Gamma, x in RR",
+            "\\mitalpha\\rightarrow\\mupGamma,x\\in ℝ",
+        ),
+        ("nabla partial infinity", "\\nabla\\partial\\infty"),
+        (
+            "lim_(x -> 0) sin x",
+            "\\operatorname{lim}_{x\\rightarrow 0}\\mathrm{sin}x",
+        ),
+        ("abs(x) <= 1", "\\left|x\\right|\\leq 1"),
+        ("upright(d) x \"if\"", "\\mathrm{d}x\\mathrm{if}"),
+    ] {
+        assert_eq!(
+            mathml_body(typst, Syntax::Typst),
+            mathml_body(latex, Syntax::Latex),
+            "{}",
+            typst
+        );
+    }
+}
+
+#[test]
+fn test_typst_diagnostics() {
+    let src = "foo(x) + bar + frac(a) + sqrt(2)";
+    let (_, diagnostics) = typst::parse_with_diagnostics::<crate::common::NoGlyph>(src);
+    let diagnostics: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| (&src[diagnostic.range.clone()], diagnostic.message.as_str()))
+        .collect();
+    assert_eq!(
+        diagnostics,
+        [
+            ("foo", "unknown function `foo`"),
+            ("bar", "unknown variable `bar`"),
+            ("frac(a)", "`frac` expects 2 argument(s), not 1"),
+        ]
+    );
+
+    // Rendering fails at the first problem
+    assert!(matches!(
+        crate::render_mathml_with(src, Syntax::Typst),
+        Err(crate::Error::Parse { position: 0 })
+    ));
+    let plus = src.find('+').unwrap();
+    assert!(matches!(
+        crate::render_mathml_with(&src[plus..], Syntax::Typst),
+        Err(crate::Error::Parse { position: 2 })
+    ));
+
+    // Unknown names are shown in the error color, followed by the arguments
+    let (list, _) = typst::parse_with_diagnostics::<crate::common::NoGlyph>("foo(x)");
+    let mut mathml = String::new();
+    crate::mathlist::mathml::write(&list, None, &mut mathml).unwrap();
+    assert!(mathml.contains("<merror><mtext>foo</mtext></merror><mrow><mo fence"));
+}
+
+#[test]
+fn test_typst_sqrt() {
+    let mathml = crate::render_mathml_with("sqrt(x + 1)", Syntax::Typst).unwrap();
+    assert!(mathml.contains("<msqrt><mrow><mi>𝑥</mi><mo>+</mo><mn>1</mn></mrow></msqrt>"));
+}
+
+#[test]
+fn test_typst_vec() {
+    // The entries are stacked in a column
+    let mathml = crate::render_mathml_with("vec(1, x + 2)", Syntax::Typst).unwrap();
+    assert!(mathml.contains(
+        "<mtable><mtr><mtd><mn>1</mn></mtd></mtr>\
+         <mtr><mtd><mrow><mi>𝑥</mi><mo>+</mo><mn>2</mn></mrow></mtd></mtr></mtable>"
+    ));
+
+    let text = crate::render_text_with("vec(1, 2)", Syntax::Typst).unwrap();
+    assert_eq!(text, "⎛1⎞\n⎜ ⎟\n⎝2⎠");
+}